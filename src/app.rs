@@ -1,22 +1,84 @@
+use std::time::Duration;
+
 use crate::graphics::Graphics;
 use crate::input::{Event, Input};
 use crate::time::Timer;
-use crate::window::Window;
+use crate::window::{Window, WindowConfig};
+
+/// Controls how `App::run` calls the update methods of the `EventHandler`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// `update` is called at the fixed tick rate. This is the default.
+    #[default]
+    Fixed,
+
+    /// `frame_update` is called once per frame, with the time since the last frame.
+    /// `update` is never called, and the loop does not wait between frames, so this
+    /// should usually be paired with vsync.
+    Variable,
+
+    /// `update` is called at the fixed tick rate, followed by `frame_update` once per
+    /// frame. `Timer::blend_factor` can be used to interpolate between fixed updates.
+    ///
+    /// Input is cleared after each fixed update, so `frame_update` may not see
+    /// one-off presses - handle those in `event` instead.
+    Hybrid,
+}
 
 /// The generic event handler for the game. You should implement this yourself
 ///
 /// ## Call order:
-/// 1. `event`: 0 to n times based on what events are received
-/// 2. `update`: 0 to n times based on the tick rate
-/// 3. `draw`: 1 time per frame
+/// 1. `init`: 1 time, before the first frame
+/// 2. `event`: 0 to n times based on what events are received, each followed by
+///    `resize`, `focus_changed` or `quit_requested` if relevant
+/// 3. `update` and `late_update`: 0 to n times based on the tick rate (not called
+///    in `LoopMode::Variable`)
+/// 4. `frame_update`: 1 time per frame (only called in `LoopMode::Variable` and `LoopMode::Hybrid`)
+/// 5. `draw`: 1 time per frame
+/// 6. `shutdown`: 1 time, after the app stops running
 pub trait EventHandler {
+    /// Runs once before the first frame, after the window and graphics context have
+    /// been created.
+    fn init(&mut self, _app: &mut App) {}
+
     /// Handle a raw event for the game. This is useful for one-off events, like key down events.
     /// For continuous events, like moving a character when you hold a key, use the update method.
     ///
     /// note: this will be called after the `app.input` has been updated with the event.
     fn event(&mut self, _app: &mut App, _event: Event) {}
     fn update(&mut self, _app: &mut App) {}
+
+    /// Runs after each `update`, before the input state is cleared. This is useful for
+    /// things that need to react to the results of the update, like cameras.
+    fn late_update(&mut self, _app: &mut App) {}
+
+    /// Runs once per frame with the time since the last frame. This is equivalent to
+    /// `Timer::frame_delta`, so it will be scaled by the time scale.
+    fn frame_update(&mut self, _app: &mut App, _dt: Duration) {}
+
     fn draw(&mut self, _app: &mut App) {}
+
+    /// Runs when the main window's size in pixels changes.
+    fn resize(&mut self, _app: &mut App, _width: u32, _height: u32) {}
+
+    /// Runs when the main window gains or loses keyboard focus.
+    fn focus_changed(&mut self, _app: &mut App, _focused: bool) {}
+
+    /// Runs when the user tries to quit the app, either via the OS or by closing the
+    /// main window. Returning `false` will stop the app from quitting.
+    fn quit_requested(&mut self, _app: &mut App) -> bool {
+        true
+    }
+
+    /// Runs once after the app stops running.
+    fn shutdown(&mut self, _app: &mut App) {}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Lifecycle {
+    Created,
+    Running,
+    ShutDown,
 }
 
 pub struct App {
@@ -24,13 +86,20 @@ pub struct App {
     pub gfx: Graphics,
     pub input: Input,
     pub timer: Timer,
+    pub loop_mode: LoopMode,
 
     pub is_running: bool,
+    quit_requested: bool,
+    lifecycle: Lifecycle,
 }
 
 impl App {
     pub fn new(title: &str, width: i32, height: i32, tick_rate: f64) -> App {
-        let mut window = Window::new(title, width, height);
+        App::with_config(WindowConfig::new(title, width, height), tick_rate)
+    }
+
+    pub fn with_config(config: WindowConfig, tick_rate: f64) -> App {
+        let mut window = Window::new(&config);
         let gfx = Graphics::new(&mut window);
         let input = Input::new();
         let timer = Timer::new(tick_rate);
@@ -40,40 +109,166 @@ impl App {
             gfx,
             input,
             timer,
+            loop_mode: LoopMode::Fixed,
 
             is_running: true,
+            quit_requested: false,
+            lifecycle: Lifecycle::Created,
         }
     }
 
     pub fn run(&mut self, event_handler: &mut impl EventHandler) {
+        self.start(event_handler);
+
         self.timer.reset();
 
         while self.is_running {
-            self.timer.tick_until_update_ready();
+            match self.loop_mode {
+                LoopMode::Fixed | LoopMode::Hybrid => self.timer.tick_until_update_ready(),
+                LoopMode::Variable => self.timer.tick(),
+            }
+
+            self.frame(event_handler);
+        }
+
+        self.stop(event_handler);
+    }
+
+    /// Runs a single iteration of the game loop, without waiting for the next update to
+    /// be ready. Returns whether the app is still running.
+    ///
+    /// This is useful for embedding the app in an external loop, or driving it from
+    /// tests (e.g. with a `ManualClock`). Unlike `run`, the timer is not reset before
+    /// the first step, so call `Timer::reset` first if time has passed since the app
+    /// was created.
+    ///
+    /// `EventHandler::init` will be called on the first step, and `EventHandler::shutdown`
+    /// will be called on the step where the app stops running.
+    pub fn step(&mut self, event_handler: &mut impl EventHandler) -> bool {
+        self.start(event_handler);
 
-            self.handle_events(event_handler);
+        if self.is_running {
+            self.timer.tick();
+
+            self.frame(event_handler);
+        }
+
+        if !self.is_running {
+            self.stop(event_handler);
+        }
 
+        self.is_running
+    }
+
+    fn start(&mut self, event_handler: &mut impl EventHandler) {
+        if self.lifecycle == Lifecycle::Created {
+            self.lifecycle = Lifecycle::Running;
+            event_handler.init(self);
+        }
+    }
+
+    fn stop(&mut self, event_handler: &mut impl EventHandler) {
+        if self.lifecycle == Lifecycle::Running {
+            self.lifecycle = Lifecycle::ShutDown;
+            event_handler.shutdown(self);
+        }
+    }
+
+    fn frame(&mut self, event_handler: &mut impl EventHandler) {
+        self.handle_events(event_handler);
+
+        if self.loop_mode == LoopMode::Variable {
+            self.timer.consume_frame();
+        } else {
             while self.timer.consume_time() {
                 event_handler.update(self);
+                event_handler.late_update(self);
 
                 self.input.clear();
             }
+        }
 
-            event_handler.draw(self);
+        if self.loop_mode != LoopMode::Fixed {
+            event_handler.frame_update(self, self.timer.frame_delta());
+        }
 
-            self.window.present();
+        // In hybrid mode, input is left for the next fixed update to consume.
+        if self.loop_mode == LoopMode::Variable {
+            self.input.clear();
         }
+
+        event_handler.draw(self);
+
+        self.window.present();
     }
 
     pub fn handle_events(&mut self, event_handler: &mut impl EventHandler) {
+        let main_window = self.window.id();
+
         while let Some(event) = self.window.next_event() {
-            if let Event::Quit = event {
-                self.is_running = false;
+            // Closing a secondary window doesn't quit the app - the game should handle
+            // that event itself.
+            let is_quit = match event {
+                Event::Quit => true,
+                Event::WindowCloseRequested { window } => window == main_window,
+                _ => false,
+            };
+
+            if is_quit {
+                self.quit_requested = true;
             }
 
-            self.input.event(&event);
+            // Mouse positions from other windows are in a different coordinate space,
+            // so the pointer state only tracks the main window.
+            let is_other_pointer = match event {
+                Event::MouseButtonDown { window, .. }
+                | Event::MouseButtonUp { window, .. }
+                | Event::MouseMotion { window, .. } => window != main_window,
+                _ => false,
+            };
+
+            if !is_other_pointer {
+                self.input.event(&event);
+            }
+
+            let resized = match event {
+                Event::WindowPixelSizeChanged {
+                    window,
+                    width,
+                    height,
+                } if window == main_window => Some((width, height)),
+                _ => None,
+            };
+
+            let focus_changed = match event {
+                Event::WindowFocusGained { window } if window == main_window => Some(true),
+                Event::WindowFocusLost { window } if window == main_window => Some(false),
+                _ => None,
+            };
 
             event_handler.event(self, event);
+
+            if let Some((width, height)) = resized {
+                event_handler.resize(self, width, height);
+            }
+
+            if let Some(focused) = focus_changed {
+                event_handler.focus_changed(self, focused);
+            }
+
+            if is_quit && self.quit_requested && !event_handler.quit_requested(self) {
+                self.quit_requested = false;
+            }
+
+            if is_quit && self.quit_requested {
+                self.is_running = false;
+            }
         }
     }
+
+    /// Stops the app from quitting. This can be called while handling a `Quit` or
+    /// `WindowCloseRequested` event, e.g. to show a "save before quitting?" prompt.
+    pub fn cancel_quit(&mut self) {
+        self.quit_requested = false;
+    }
 }
@@ -0,0 +1,5 @@
+mod loader;
+mod store;
+
+pub use self::loader::*;
+pub use self::store::*;
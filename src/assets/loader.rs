@@ -0,0 +1,283 @@
+use std::any::Any;
+use std::cell::{Ref, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::graphics::{Font, Graphics, ImageData, SpriteFont, Texture};
+
+#[cfg(feature = "ldtk")]
+use crate::ldtk::Project;
+
+type JobResult = Result<Box<dyn Any + Send>, String>;
+type Job = Box<dyn FnOnce() -> JobResult + Send>;
+type Finisher = Box<dyn FnOnce(&Graphics, JobResult)>;
+
+#[derive(Debug)]
+pub enum LoadState<T> {
+    Loading,
+    Ready(T),
+    Failed(String),
+}
+
+/// A handle to an asset that is being loaded by an `AssetLoader`.
+///
+/// Handles are cheap to clone, and all clones will see the asset once it is ready.
+pub struct AssetHandle<T>(Rc<RefCell<LoadState<T>>>);
+
+impl<T> AssetHandle<T> {
+    pub fn state(&self) -> Ref<'_, LoadState<T>> {
+        self.0.borrow()
+    }
+
+    pub fn is_loading(&self) -> bool {
+        matches!(*self.0.borrow(), LoadState::Loading)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        matches!(*self.0.borrow(), LoadState::Ready(_))
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(*self.0.borrow(), LoadState::Failed(_))
+    }
+
+    /// Returns the asset, if it has finished loading.
+    pub fn get(&self) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.0.borrow(), |state| match state {
+            LoadState::Ready(asset) => Some(asset),
+            _ => None,
+        })
+        .ok()
+    }
+
+    /// Returns the reason that loading failed, if it did.
+    pub fn error(&self) -> Option<String> {
+        match &*self.0.borrow() {
+            LoadState::Failed(error) => Some(error.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Clone for AssetHandle<T> {
+    fn clone(&self) -> Self {
+        AssetHandle(Rc::clone(&self.0))
+    }
+}
+
+/// Loads assets in the background.
+///
+/// Files are read and decoded on a pool of worker threads. As `Graphics` can only be used
+/// from the main thread, anything that needs to be uploaded to the GPU is then queued up
+/// until `update` is called, which will do as many uploads as it can within the upload
+/// budget. This keeps the game responsive while loading, e.g. to animate a loading screen.
+///
+/// Other kinds of asset, like audio, can be loaded via `load_with`.
+pub struct AssetLoader {
+    gfx: Graphics,
+
+    jobs: Sender<(u64, Job)>,
+    results: Receiver<(u64, JobResult)>,
+
+    next_id: u64,
+    finishers: HashMap<u64, Finisher>,
+    ready: VecDeque<(u64, JobResult)>,
+
+    upload_budget: Duration,
+}
+
+impl AssetLoader {
+    pub fn new(gfx: &Graphics) -> AssetLoader {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get().clamp(1, 4))
+            .unwrap_or(1);
+
+        AssetLoader::with_threads(gfx, threads)
+    }
+
+    pub fn with_threads(gfx: &Graphics, threads: usize) -> AssetLoader {
+        let (job_sender, job_receiver) = mpsc::channel::<(u64, Job)>();
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        for i in 0..threads.max(1) {
+            let job_receiver = Arc::clone(&job_receiver);
+            let result_sender = result_sender.clone();
+
+            thread::Builder::new()
+                .name(format!("nova-asset-loader-{}", i))
+                .spawn(move || loop {
+                    // The lock is released as soon as a job has been received.
+                    let job = job_receiver.lock().unwrap().recv();
+
+                    // The channel closes when the loader is dropped, at which point
+                    // the worker can shut down.
+                    let Ok((id, job)) = job else {
+                        break;
+                    };
+
+                    let result = panic::catch_unwind(AssertUnwindSafe(job))
+                        .unwrap_or_else(|payload| Err(panic_message(payload)));
+
+                    if result_sender.send((id, result)).is_err() {
+                        break;
+                    }
+                })
+                .unwrap();
+        }
+
+        AssetLoader {
+            gfx: gfx.clone(),
+
+            jobs: job_sender,
+            results: result_receiver,
+
+            next_id: 0,
+            finishers: HashMap::new(),
+            ready: VecDeque::new(),
+
+            upload_budget: Duration::from_millis(4),
+        }
+    }
+
+    /// Sets how much time `update` can spend uploading assets to the GPU. At least one
+    /// asset will be uploaded per update, even if it takes longer than this.
+    pub fn set_upload_budget(&mut self, budget: Duration) {
+        self.upload_budget = budget;
+    }
+
+    pub fn upload_budget(&self) -> Duration {
+        self.upload_budget
+    }
+
+    /// Returns the number of assets that have been requested but have not yet finished
+    /// loading.
+    pub fn pending(&self) -> usize {
+        self.finishers.len()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.finishers.is_empty()
+    }
+
+    pub fn load_texture(&mut self, path: &str, premultiply: bool) -> AssetHandle<Texture> {
+        let path = path.to_owned();
+
+        self.load_with(
+            move || ImageData::try_from_file(&path, premultiply),
+            |gfx, image| Texture::from_image(gfx, &image),
+        )
+    }
+
+    pub fn load_image(&mut self, path: &str, premultiply: bool) -> AssetHandle<ImageData> {
+        let path = path.to_owned();
+
+        self.load_with(
+            move || ImageData::try_from_file(&path, premultiply),
+            |_, image| image,
+        )
+    }
+
+    pub fn load_font(&mut self, path: &str) -> AssetHandle<Font> {
+        let path = path.to_owned();
+
+        self.load_with(move || Font::try_from_file(&path), |_, font| font)
+    }
+
+    /// Loads a font and rasterizes it into a `SpriteFont` at the given size.
+    pub fn load_sprite_font(&mut self, path: &str, size: f32) -> AssetHandle<SpriteFont> {
+        let path = path.to_owned();
+
+        self.load_with(
+            move || Font::try_from_file(&path),
+            move |gfx, font| SpriteFont::new(gfx, &font, size),
+        )
+    }
+
+    #[cfg(feature = "ldtk")]
+    pub fn load_ldtk(&mut self, path: &str) -> AssetHandle<Project> {
+        let path = path.to_owned();
+
+        self.load_with(move || Project::try_from_file(&path), |_, project| project)
+    }
+
+    /// Loads a custom asset. `load` runs on a worker thread, and `finish` runs on the
+    /// main thread during `update`, where it can create GPU resources.
+    ///
+    /// If `load` returns an error (or panics), the handle will be marked as failed and
+    /// `finish` will not be called. `finish` should not fail - anything that can go wrong
+    /// should be checked in `load` instead.
+    pub fn load_with<D, T>(
+        &mut self,
+        load: impl FnOnce() -> Result<D, String> + Send + 'static,
+        finish: impl FnOnce(&Graphics, D) -> T + 'static,
+    ) -> AssetHandle<T>
+    where
+        D: Send + 'static,
+        T: 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let handle = AssetHandle(Rc::new(RefCell::new(LoadState::Loading)));
+        let finish_handle = handle.clone();
+
+        self.finishers.insert(
+            id,
+            Box::new(move |gfx, result| {
+                let state = match result {
+                    Ok(data) => {
+                        let data = *data.downcast::<D>().expect("asset data should match");
+
+                        LoadState::Ready(finish(gfx, data))
+                    }
+                    Err(error) => LoadState::Failed(error),
+                };
+
+                *finish_handle.0.borrow_mut() = state;
+            }),
+        );
+
+        let job: Job = Box::new(move || load().map(|data| Box::new(data) as Box<dyn Any + Send>));
+
+        self.jobs
+            .send((id, job))
+            .expect("worker threads should be running");
+
+        handle
+    }
+
+    /// Finishes loading any assets that are ready, within the upload budget. This
+    /// should be called once per frame.
+    pub fn update(&mut self) {
+        self.ready.extend(self.results.try_iter());
+
+        let start = Instant::now();
+
+        while let Some((id, result)) = self.ready.pop_front() {
+            if let Some(finish) = self.finishers.remove(&id) {
+                finish(&self.gfx, result);
+            }
+
+            if start.elapsed() >= self.upload_budget {
+                break;
+            }
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown error".to_string()
+    }
+}
@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::fs;
+use crate::graphics::{Font, Graphics, ImageData, Shader, SpriteFont, Texture};
+use crate::input::EventSender;
+
+#[cfg(feature = "ldtk")]
+use crate::ldtk::Project;
+
+/// An asset that can be stored in a cache, and counts how many handles to it exist.
+trait Shared: Clone {
+    fn handle_count(&self) -> usize;
+}
+
+impl Shared for Texture {
+    fn handle_count(&self) -> usize {
+        Rc::strong_count(&self.raw)
+    }
+}
+
+impl Shared for Shader {
+    fn handle_count(&self) -> usize {
+        Rc::strong_count(&self.raw)
+    }
+}
+
+impl<T> Shared for Rc<T> {
+    fn handle_count(&self) -> usize {
+        Rc::strong_count(self)
+    }
+}
+
+/// A cache key, which may refer to one or more files.
+trait AssetKey: Eq + Hash {
+    fn uses_path(&self, path: &str) -> bool;
+}
+
+impl AssetKey for String {
+    fn uses_path(&self, path: &str) -> bool {
+        self == path
+    }
+}
+
+impl AssetKey for (String, u32) {
+    fn uses_path(&self, path: &str) -> bool {
+        self.0 == path
+    }
+}
+
+impl AssetKey for (String, String) {
+    fn uses_path(&self, path: &str) -> bool {
+        self.0 == path || self.1 == path
+    }
+}
+
+struct Entry<T> {
+    asset: T,
+    groups: Vec<String>,
+    pinned: bool,
+}
+
+struct Cache<K, T> {
+    entries: HashMap<K, Entry<T>>,
+}
+
+impl<K, T> Cache<K, T>
+where
+    K: AssetKey,
+    T: Shared,
+{
+    fn new() -> Cache<K, T> {
+        Cache {
+            entries: HashMap::new(),
+        }
+    }
+
+    fn get_or_load(&mut self, key: K, group: Option<&str>, load: impl FnOnce() -> T) -> T {
+        let entry = self.entries.entry(key).or_insert_with(|| Entry {
+            asset: load(),
+            groups: Vec::new(),
+            pinned: false,
+        });
+
+        match group {
+            Some(group) => {
+                if !entry.groups.iter().any(|g| g == group) {
+                    entry.groups.push(group.to_owned());
+                }
+            }
+            None => entry.pinned = true,
+        }
+
+        entry.asset.clone()
+    }
+
+    fn unload_group(&mut self, group: &str) {
+        self.entries.retain(|_, entry| {
+            entry.groups.retain(|g| g != group);
+            entry.pinned || !entry.groups.is_empty()
+        });
+    }
+
+    fn unload_unused(&mut self) {
+        self.entries
+            .retain(|_, entry| entry.asset.handle_count() > 1);
+    }
+
+    fn unload_path(&mut self, path: &str) {
+        self.entries.retain(|key, _| !key.uses_path(path));
+    }
+
+    fn handle_count(&self, path: &str) -> usize {
+        self.entries
+            .iter()
+            .filter(|(key, _)| key.uses_path(path))
+            .map(|(_, entry)| entry.asset.handle_count() - 1)
+            .sum()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Reloads every asset that uses the path. `reload` can either update the asset in
+    /// place and return `None`, or return a replacement. Returns whether any assets
+    /// were reloaded, and adds any failures to `errors`.
+    fn reload_path(
+        &mut self,
+        path: &str,
+        errors: &mut Vec<String>,
+        mut reload: impl FnMut(&K, &T) -> Result<Option<T>, String>,
+    ) -> bool {
+        let mut reloaded = false;
+
+        for (key, entry) in &mut self.entries {
+            if !key.uses_path(path) {
+                continue;
+            }
+
+            match reload(key, &entry.asset) {
+                Ok(Some(asset)) => {
+                    entry.asset = asset;
+                    reloaded = true;
+                }
+                Ok(None) => reloaded = true,
+                Err(e) => errors.push(e),
+            }
+        }
+
+        reloaded
+    }
+}
+
+/// A message that is sent when `Assets` reloads a file. It will be received as an
+/// `Event::User`.
+///
+/// Textures and shaders are updated in place, but other assets are replaced, so any
+/// handles to them should be fetched from the cache again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetReloaded {
+    pub path: String,
+}
+
+/// A message that is sent when `Assets` fails to reload a file. It will be received as
+/// an `Event::User`.
+///
+/// The old version of the asset is kept, so the game can carry on running while the
+/// file is fixed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetReloadFailed {
+    pub path: String,
+    pub error: String,
+}
+
+/// A cache of loaded assets, keyed by their path in the virtual filesystem.
+///
+/// Loading the same file twice will return another handle to the existing asset, rather
+/// than loading it again. Textures and shaders are already cheap to clone, and other
+/// assets are wrapped in an `Rc`. Paths are normalized first, so `a.png` and `./a.png`
+/// refer to the same asset.
+///
+/// Assets can be tagged with a group (e.g. the current level) via `set_group`, and then
+/// dropped from the cache all at once via `unload_group`. Assets loaded while no group
+/// is set stay loaded until they are explicitly unloaded. Unloading an asset only removes
+/// it from the cache - it will not be freed until all of its handles are dropped.
+///
+/// If hot reloading is enabled, `update` will periodically check whether any of the
+/// loaded files have been modified, and reload them.
+pub struct Assets {
+    gfx: Graphics,
+    group: Option<String>,
+
+    hot_reload: bool,
+    hot_reload_interval: Duration,
+    last_check: Instant,
+    modified_times: HashMap<String, Option<SystemTime>>,
+    reload_events: EventSender<AssetReloaded>,
+    reload_failed_events: EventSender<AssetReloadFailed>,
+
+    textures: Cache<String, Texture>,
+    sprite_fonts: Cache<(String, u32), Rc<SpriteFont>>,
+    shaders: Cache<(String, String), Shader>,
+
+    #[cfg(feature = "ldtk")]
+    projects: Cache<String, Rc<Project>>,
+}
+
+impl Assets {
+    pub fn new(gfx: &Graphics) -> Assets {
+        Assets {
+            gfx: gfx.clone(),
+            group: None,
+
+            hot_reload: false,
+            hot_reload_interval: Duration::from_millis(500),
+            last_check: Instant::now(),
+            modified_times: HashMap::new(),
+            reload_events: EventSender::new(),
+            reload_failed_events: EventSender::new(),
+
+            textures: Cache::new(),
+            sprite_fonts: Cache::new(),
+            shaders: Cache::new(),
+
+            #[cfg(feature = "ldtk")]
+            projects: Cache::new(),
+        }
+    }
+
+    /// Sets the group that assets will be tagged with when they are loaded. If an asset
+    /// is already loaded, it will be added to the group too.
+    pub fn set_group(&mut self, group: Option<&str>) {
+        self.group = group.map(str::to_owned);
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Loads a texture, premultiplying its alpha to match the blend mode used by
+    /// `Graphics`.
+    pub fn texture(&mut self, path: &str) -> Texture {
+        let path = fs::normalize(path);
+
+        self.watch(&path);
+
+        let gfx = &self.gfx;
+
+        self.textures
+            .get_or_load(path.clone(), self.group.as_deref(), || {
+                Texture::from_file(gfx, &path, true)
+            })
+    }
+
+    /// Loads a font and rasterizes it at the given size. Each size is cached separately.
+    pub fn sprite_font(&mut self, path: &str, size: f32) -> Rc<SpriteFont> {
+        let path = fs::normalize(path);
+
+        self.watch(&path);
+
+        let gfx = &self.gfx;
+
+        self.sprite_fonts.get_or_load(
+            (path.clone(), size.to_bits()),
+            self.group.as_deref(),
+            || Rc::new(SpriteFont::new(gfx, &Font::from_file(&path), size)),
+        )
+    }
+
+    pub fn shader(&mut self, vertex_path: &str, fragment_path: &str) -> Shader {
+        let vertex_path = fs::normalize(vertex_path);
+        let fragment_path = fs::normalize(fragment_path);
+
+        self.watch(&vertex_path);
+        self.watch(&fragment_path);
+
+        let gfx = &self.gfx;
+
+        self.shaders.get_or_load(
+            (vertex_path.clone(), fragment_path.clone()),
+            self.group.as_deref(),
+            || {
+                Shader::from_str(
+                    gfx,
+                    &fs::read_to_string(&vertex_path),
+                    &fs::read_to_string(&fragment_path),
+                )
+            },
+        )
+    }
+
+    #[cfg(feature = "ldtk")]
+    pub fn ldtk(&mut self, path: &str) -> Rc<Project> {
+        let path = fs::normalize(path);
+
+        self.watch(&path);
+
+        self.projects
+            .get_or_load(path.clone(), self.group.as_deref(), || {
+                Rc::new(Project::from_file(&path))
+            })
+    }
+
+    /// Removes every asset that was tagged with the group, unless it also belongs to
+    /// another group, or was loaded while no group was set.
+    pub fn unload_group(&mut self, group: &str) {
+        self.textures.unload_group(group);
+        self.sprite_fonts.unload_group(group);
+        self.shaders.unload_group(group);
+
+        #[cfg(feature = "ldtk")]
+        self.projects.unload_group(group);
+    }
+
+    /// Removes every asset that has no handles outside of the cache.
+    pub fn unload_unused(&mut self) {
+        self.textures.unload_unused();
+        self.sprite_fonts.unload_unused();
+        self.shaders.unload_unused();
+
+        #[cfg(feature = "ldtk")]
+        self.projects.unload_unused();
+    }
+
+    /// Removes every asset that was loaded from the given path.
+    pub fn unload(&mut self, path: &str) {
+        let path = &fs::normalize(path);
+
+        self.textures.unload_path(path);
+        self.sprite_fonts.unload_path(path);
+        self.shaders.unload_path(path);
+
+        #[cfg(feature = "ldtk")]
+        self.projects.unload_path(path);
+    }
+
+    /// Returns how many handles to assets loaded from the given path exist outside of
+    /// the cache.
+    pub fn handle_count(&self, path: &str) -> usize {
+        let path = &fs::normalize(path);
+
+        let count = self.textures.handle_count(path)
+            + self.sprite_fonts.handle_count(path)
+            + self.shaders.handle_count(path);
+
+        #[cfg(feature = "ldtk")]
+        let count = count + self.projects.handle_count(path);
+
+        count
+    }
+
+    /// Returns the number of assets in the cache.
+    pub fn len(&self) -> usize {
+        let len = self.textures.len() + self.sprite_fonts.len() + self.shaders.len();
+
+        #[cfg(feature = "ldtk")]
+        let len = len + self.projects.len();
+
+        len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Enables or disables hot reloading. This is off by default.
+    pub fn set_hot_reload(&mut self, enabled: bool) {
+        self.hot_reload = enabled;
+    }
+
+    pub fn is_hot_reload_enabled(&self) -> bool {
+        self.hot_reload
+    }
+
+    /// Sets how often `update` checks for modified files.
+    pub fn set_hot_reload_interval(&mut self, interval: Duration) {
+        self.hot_reload_interval = interval;
+    }
+
+    /// Checks for modified files if hot reloading is enabled. This should be called
+    /// once per frame or tick.
+    pub fn update(&mut self) {
+        if self.hot_reload && self.last_check.elapsed() >= self.hot_reload_interval {
+            self.last_check = Instant::now();
+            self.reload_modified();
+        }
+    }
+
+    /// Immediately reloads any loaded files that have been modified since they were
+    /// last loaded, regardless of whether hot reloading is enabled.
+    ///
+    /// If a file fails to load, the old version is kept, and an `AssetReloadFailed`
+    /// message is sent for each asset that could not be reloaded.
+    pub fn reload_modified(&mut self) {
+        let mut modified = Vec::new();
+
+        for (path, time) in &mut self.modified_times {
+            let current = modified_time(path);
+
+            if current != *time {
+                *time = current;
+                modified.push(path.clone());
+            }
+        }
+
+        for path in modified {
+            let mut errors = Vec::new();
+
+            let reloaded = self.reload(&path, &mut errors);
+
+            for error in errors {
+                self.reload_failed_events.send(AssetReloadFailed {
+                    path: path.clone(),
+                    error,
+                });
+            }
+
+            if reloaded {
+                self.reload_events.send(AssetReloaded { path });
+            }
+        }
+    }
+
+    fn reload(&mut self, path: &str, errors: &mut Vec<String>) -> bool {
+        let gfx = &self.gfx;
+        let mut reloaded = false;
+
+        reloaded |= self.textures.reload_path(path, errors, |_, texture| {
+            let image = ImageData::try_from_file(path, true)?;
+            texture.set_image(&image);
+
+            Ok(None)
+        });
+
+        reloaded |= self
+            .sprite_fonts
+            .reload_path(path, errors, |_, sprite_font| {
+                let font = Font::try_from_file(path)?;
+
+                Ok(Some(Rc::new(SpriteFont::with_scale(
+                    gfx,
+                    &font,
+                    sprite_font.size(),
+                    sprite_font.scale(),
+                ))))
+            });
+
+        reloaded |=
+            self.shaders
+                .reload_path(path, errors, |(vertex_path, fragment_path), shader| {
+                    let vertex_src = read_shader(vertex_path)?;
+                    let fragment_src = read_shader(fragment_path)?;
+
+                    shader.reload(&vertex_src, &fragment_src)?;
+
+                    Ok(None)
+                });
+
+        #[cfg(feature = "ldtk")]
+        {
+            reloaded |= self.projects.reload_path(path, errors, |_, _| {
+                let project = Project::try_from_file(path)?;
+
+                Ok(Some(Rc::new(project)))
+            });
+        }
+
+        reloaded
+    }
+
+    /// Paths should already be normalized, so that each file is only watched once.
+    fn watch(&mut self, path: &str) {
+        if !self.modified_times.contains_key(path) {
+            self.modified_times
+                .insert(path.to_owned(), modified_time(path));
+        }
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    // Files inside archives can't change while the game is running.
+    fs::real_path(path)?
+        .metadata()
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+fn read_shader(path: &str) -> Result<String, String> {
+    fs::try_read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))
+}
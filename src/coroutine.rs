@@ -0,0 +1,210 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use crate::input::Input;
+
+thread_local! {
+    static CURRENT_INPUT: Cell<*const Input> = const { Cell::new(std::ptr::null()) };
+}
+
+/// Identifies a coroutine that has been spawned on a `Coroutines` executor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CoroutineID(u64);
+
+struct Coroutine {
+    id: CoroutineID,
+    future: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+/// Runs async functions one step at a time, once per update tick.
+///
+/// This allows scripted sequences, like cutscenes or enemy patterns, to be written
+/// top-to-bottom, using the `wait_*` functions in this module to pause between steps.
+/// Each coroutine is polled exactly once per call to `update`, so waiting is measured
+/// in ticks and stays in step with the fixed-rate game loop.
+///
+/// Coroutines are not `Send`, so they can hold handles like `Rc` - to share state with
+/// the rest of the game, capture an `Rc<RefCell<T>>` or similar.
+pub struct Coroutines {
+    coroutines: Vec<Coroutine>,
+    next_id: u64,
+}
+
+impl Coroutines {
+    pub fn new() -> Coroutines {
+        Coroutines {
+            coroutines: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Starts running a coroutine. It will first be polled on the next `update`.
+    pub fn spawn(&mut self, future: impl Future<Output = ()> + 'static) -> CoroutineID {
+        let id = CoroutineID(self.next_id);
+        self.next_id += 1;
+
+        self.coroutines.push(Coroutine {
+            id,
+            future: Box::pin(future),
+        });
+
+        id
+    }
+
+    /// Stops a coroutine, dropping it at whatever point it was waiting.
+    pub fn cancel(&mut self, id: CoroutineID) {
+        self.coroutines.retain(|c| c.id != id);
+    }
+
+    pub fn cancel_all(&mut self) {
+        self.coroutines.clear();
+    }
+
+    pub fn is_running(&self, id: CoroutineID) -> bool {
+        self.coroutines.iter().any(|c| c.id == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.coroutines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coroutines.is_empty()
+    }
+
+    /// Advances every coroutine by one tick. This should be called once from
+    /// `EventHandler::update`, before the input is cleared.
+    pub fn update(&mut self, input: &Input) {
+        let _guard = InputGuard::set(input);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        self.coroutines
+            .retain_mut(|c| c.future.as_mut().poll(&mut cx).is_pending());
+    }
+}
+
+/// Makes the input available to `wait_for_action`, restoring the previous value when
+/// dropped (even if a coroutine panics).
+struct InputGuard {
+    previous: *const Input,
+}
+
+impl InputGuard {
+    fn set(input: &Input) -> InputGuard {
+        InputGuard {
+            previous: CURRENT_INPUT.with(|current| current.replace(input)),
+        }
+    }
+}
+
+impl Drop for InputGuard {
+    fn drop(&mut self) {
+        CURRENT_INPUT.with(|current| current.set(self.previous));
+    }
+}
+
+/// Waits for the given number of ticks to pass.
+///
+/// Waiting for zero ticks completes immediately.
+pub fn wait_ticks(ticks: u32) -> WaitTicks {
+    WaitTicks { remaining: ticks }
+}
+
+/// Waits until the given condition returns true. It is checked once per tick, starting
+/// with the current one.
+pub fn wait_until<F>(condition: F) -> WaitUntil<F>
+where
+    F: FnMut() -> bool,
+{
+    WaitUntil { condition }
+}
+
+/// Waits until the given condition on the input state returns true, e.g. for a key to
+/// be pressed. It is checked once per tick, starting with the next one, so that a single
+/// press can't complete two waits in a row.
+///
+/// # Panics
+///
+/// This will panic if it is polled outside of `Coroutines::update`.
+pub fn wait_for_action<F>(action: F) -> WaitForAction<F>
+where
+    F: FnMut(&Input) -> bool,
+{
+    WaitForAction {
+        action,
+        started: false,
+    }
+}
+
+pub struct WaitTicks {
+    remaining: u32,
+}
+
+impl Future for WaitTicks {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.remaining == 0 {
+            Poll::Ready(())
+        } else {
+            self.remaining -= 1;
+            Poll::Pending
+        }
+    }
+}
+
+pub struct WaitUntil<F> {
+    condition: F,
+}
+
+impl<F> Future for WaitUntil<F>
+where
+    F: FnMut() -> bool + Unpin,
+{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if (self.condition)() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+pub struct WaitForAction<F> {
+    action: F,
+    started: bool,
+}
+
+impl<F> Future for WaitForAction<F>
+where
+    F: FnMut(&Input) -> bool + Unpin,
+{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if !self.started {
+            self.started = true;
+            return Poll::Pending;
+        }
+
+        let input = CURRENT_INPUT.with(|current| current.get());
+
+        if input.is_null() {
+            panic!("wait_for_action must be polled by Coroutines::update");
+        }
+
+        // SAFETY: The pointer is only set for the duration of `Coroutines::update`,
+        // which holds a shared borrow of the input.
+        let input = unsafe { &*input };
+
+        if (self.action)(input) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
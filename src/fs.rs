@@ -1,25 +1,388 @@
-use std::path::PathBuf;
-use std::sync::OnceLock;
+mod archive;
 
+use std::ffi::CStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock, RwLockReadGuard};
+
+use sdl3_sys::filesystem::SDL_GetBasePath;
+
+/// Somewhere that files can be read from, e.g. a directory or an archive.
+///
+/// Paths are always normalized and relative to the source's mount point.
+trait Source: Send + Sync {
+    /// Returns `None` if the file does not exist in this source.
+    fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>>;
+
+    fn exists(&self, path: &str) -> bool;
+
+    /// Adds the names of the files and directories directly inside `dir` to `out`.
+    fn list(&self, dir: &str, out: &mut Vec<String>);
+
+    /// Returns where the file is on disk, if it is a real file.
+    fn real_path(&self, path: &str) -> Option<PathBuf>;
+}
+
+struct Directory {
+    root: PathBuf,
+}
+
+impl Source for Directory {
+    fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>> {
+        let full_path = self.root.join(path);
+
+        if full_path.is_file() {
+            Some(std::fs::read(full_path))
+        } else {
+            None
+        }
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.root.join(path).exists()
+    }
+
+    fn list(&self, dir: &str, out: &mut Vec<String>) {
+        if let Ok(entries) = std::fs::read_dir(self.root.join(dir)) {
+            for entry in entries.flatten() {
+                out.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    fn real_path(&self, path: &str) -> Option<PathBuf> {
+        let full_path = self.root.join(path);
+
+        if full_path.exists() {
+            Some(full_path)
+        } else {
+            None
+        }
+    }
+}
+
+struct Mount {
+    point: String,
+    source: Box<dyn Source>,
+}
+
+impl Mount {
+    /// Converts a VFS path into a path relative to this mount, if it is inside it.
+    fn relative<'a>(&self, path: &'a str) -> Option<&'a str> {
+        if self.point.is_empty() {
+            Some(path)
+        } else if path == self.point {
+            Some("")
+        } else {
+            path.strip_prefix(&self.point)?.strip_prefix('/')
+        }
+    }
+}
+
+fn mounts() -> &'static RwLock<Vec<Mount>> {
+    static MOUNTS: OnceLock<RwLock<Vec<Mount>>> = OnceLock::new();
+
+    MOUNTS.get_or_init(|| {
+        let mut mounts = Vec::new();
+
+        // Mounted first, so that files in the base path take priority.
+        if let Some(dir) = executable_dir().filter(|dir| dir != base_path()) {
+            mounts.push(Mount {
+                point: String::new(),
+                source: Box::new(Directory { root: dir }),
+            });
+        }
+
+        mounts.push(Mount {
+            point: String::new(),
+            source: Box::new(Directory {
+                root: base_path().clone(),
+            }),
+        });
+
+        RwLock::new(mounts)
+    })
+}
+
+fn read_mounts() -> RwLockReadGuard<'static, Vec<Mount>> {
+    mounts().read().unwrap()
+}
+
+/// Returns the working directory that the game was launched from, which is mounted at
+/// the root of the virtual filesystem by default.
+///
+/// The directory that the executable is in is also mounted at the root, at a lower
+/// priority, so that packaged games can find their assets when launched from elsewhere.
 pub fn base_path() -> &'static PathBuf {
     static BASE_PATH: OnceLock<PathBuf> = OnceLock::new();
 
-    // TODO: Make this use SDL_GetBaseDir when packaging for release
     BASE_PATH.get_or_init(|| std::env::current_dir().unwrap())
 }
 
+fn executable_dir() -> Option<PathBuf> {
+    unsafe {
+        let raw = SDL_GetBasePath();
+
+        if raw.is_null() {
+            return None;
+        }
+
+        Some(PathBuf::from(
+            CStr::from_ptr(raw).to_string_lossy().into_owned(),
+        ))
+    }
+}
+
+/// Joins a path onto the base path.
+///
+/// This does not take mounts into account, so the file may not be the one that
+/// `read` would return. Use `real_path` to find out where a file in the virtual
+/// filesystem is on disk.
 pub fn asset_path(path: &str) -> PathBuf {
     base_path().join(path)
 }
 
-pub fn read(path: &str) -> Vec<u8> {
-    let full_path = asset_path(path);
+/// Mounts a directory into the virtual filesystem, at the given mount point.
+///
+/// The base path and the executable's directory are mounted at the root by default.
+/// Mounts are searched from newest to oldest, so files in later mounts will override
+/// files in earlier ones - this can be used for mods and patches.
+pub fn mount_dir(mount_point: &str, dir: impl AsRef<Path>) -> io::Result<()> {
+    let dir = dir.as_ref();
+
+    if !dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not a directory", dir.display()),
+        ));
+    }
+
+    add_mount(
+        mount_point,
+        Box::new(Directory {
+            root: dir.to_owned(),
+        }),
+    );
+
+    Ok(())
+}
+
+/// Mounts a zip or PAK archive into the virtual filesystem, at the given mount point.
+///
+/// Zip archives must use either no compression or deflate, and zip64 is not supported.
+/// Deflate requires the `zip` feature, which is enabled by default.
+pub fn mount_archive(mount_point: &str, path: impl AsRef<Path>) -> io::Result<()> {
+    let archive = archive::Archive::open(path.as_ref())?;
+
+    add_mount(mount_point, Box::new(archive));
+
+    Ok(())
+}
+
+/// Removes every mount at the given mount point, including the default mounts if the
+/// mount point is empty.
+pub fn unmount(mount_point: &str) {
+    let mount_point = normalize(mount_point);
+
+    mounts().write().unwrap().retain(|m| m.point != mount_point);
+}
 
-    std::fs::read(full_path).unwrap()
+fn add_mount(mount_point: &str, source: Box<dyn Source>) {
+    mounts().write().unwrap().push(Mount {
+        point: normalize(mount_point),
+        source,
+    });
+}
+
+/// Reads a file from the virtual filesystem.
+pub fn try_read(path: &str) -> io::Result<Vec<u8>> {
+    let path = normalize(path);
+
+    for mount in read_mounts().iter().rev() {
+        if let Some(relative) = mount.relative(&path) {
+            if let Some(result) = mount.source.read(relative) {
+                return result;
+            }
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "file not found in any mount",
+    ))
+}
+
+/// Reads a UTF-8 text file from the virtual filesystem.
+pub fn try_read_to_string(path: &str) -> io::Result<String> {
+    String::from_utf8(try_read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn read(path: &str) -> Vec<u8> {
+    try_read(path).unwrap()
 }
 
 pub fn read_to_string(path: &str) -> String {
-    let full_path = asset_path(path);
+    try_read_to_string(path).unwrap()
+}
+
+/// Returns whether a file or directory exists in the virtual filesystem.
+pub fn exists(path: &str) -> bool {
+    let path = normalize(path);
+
+    read_mounts().iter().any(|mount| {
+        mount
+            .relative(&path)
+            .is_some_and(|relative| mount.source.exists(relative))
+            || is_parent_of_mount(&path, &mount.point)
+    })
+}
+
+/// Lists the names of the files and directories inside a directory in the virtual
+/// filesystem, merged across all mounts and sorted.
+pub fn list(dir: &str) -> Vec<String> {
+    let dir = normalize(dir);
+    let mut names = Vec::new();
+
+    for mount in read_mounts().iter() {
+        if let Some(relative) = mount.relative(&dir) {
+            mount.source.list(relative, &mut names);
+        } else if is_parent_of_mount(&dir, &mount.point) {
+            // Mount points show up as directories in their parent.
+            let rest = if dir.is_empty() {
+                &mount.point[..]
+            } else {
+                &mount.point[dir.len() + 1..]
+            };
+
+            names.push(rest.split('/').next().unwrap_or(rest).to_owned());
+        }
+    }
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Returns where a file is on disk, if it comes from a mounted directory rather than
+/// an archive. This is useful for watching files for changes.
+pub fn real_path(path: &str) -> Option<PathBuf> {
+    let path = normalize(path);
+
+    read_mounts().iter().rev().find_map(|mount| {
+        mount
+            .relative(&path)
+            .and_then(|relative| mount.source.real_path(relative))
+    })
+}
+
+fn is_parent_of_mount(dir: &str, mount_point: &str) -> bool {
+    !mount_point.is_empty()
+        && (dir.is_empty()
+            || mount_point
+                .strip_prefix(dir)
+                .is_some_and(|rest| rest.starts_with('/')))
+}
+
+/// Converts a path into the form used by the virtual filesystem: forward slashes, no
+/// leading or trailing slashes, and no `.` or `..` components.
+pub(crate) fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+
+    for part in path.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nova-test-{}", name));
+
+        for (path, contents) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(normalize("a/b.png"), "a/b.png");
+        assert_eq!(normalize("./a/b.png"), "a/b.png");
+        assert_eq!(normalize("/a//b.png/"), "a/b.png");
+        assert_eq!(normalize("a\\b.png"), "a/b.png");
+        assert_eq!(normalize("a/c/../b.png"), "a/b.png");
+        assert_eq!(normalize("../a.png"), "a.png");
+        assert_eq!(normalize("."), "");
+    }
+
+    #[test]
+    fn finds_paths_relative_to_mount() {
+        let mount = Mount {
+            point: "mods/a".to_owned(),
+            source: Box::new(Directory {
+                root: PathBuf::new(),
+            }),
+        };
+
+        assert_eq!(mount.relative("mods/a/b.png"), Some("b.png"));
+        assert_eq!(mount.relative("mods/a"), Some(""));
+        assert_eq!(mount.relative("mods/ab.png"), None);
+        assert_eq!(mount.relative("b.png"), None);
+
+        assert!(is_parent_of_mount("", "mods/a"));
+        assert!(is_parent_of_mount("mods", "mods/a"));
+        assert!(!is_parent_of_mount("mod", "mods/a"));
+        assert!(!is_parent_of_mount("mods/a", "mods/a"));
+    }
+
+    #[test]
+    fn later_mounts_override_earlier_ones() {
+        let base = temp_dir(
+            "overlay-base",
+            &[("a.txt", "base"), ("b.txt", "base"), ("dir/c.txt", "base")],
+        );
+        let patch = temp_dir("overlay-patch", &[("a.txt", "patch"), ("d.txt", "patch")]);
+
+        mount_dir("overlay", &base).unwrap();
+        mount_dir("overlay", &patch).unwrap();
+
+        assert_eq!(read_to_string("overlay/a.txt"), "patch");
+        assert_eq!(read_to_string("overlay/b.txt"), "base");
+        assert_eq!(read_to_string("./overlay/dir/../d.txt"), "patch");
+        assert!(try_read("overlay/e.txt").is_err());
+
+        assert_eq!(list("overlay"), ["a.txt", "b.txt", "d.txt", "dir"]);
+        assert_eq!(real_path("overlay/a.txt"), Some(patch.join("a.txt")));
+
+        unmount("overlay");
+
+        assert!(!exists("overlay/a.txt"));
+    }
+
+    #[test]
+    fn mount_points_are_listed_as_directories() {
+        let dir = temp_dir("nested-mount", &[("a.txt", "nested")]);
+
+        mount_dir("nested/mount", &dir).unwrap();
+
+        assert!(exists("nested"));
+        assert!(exists("nested/mount"));
+        assert_eq!(list("nested"), ["mount"]);
+        assert_eq!(list("nested/mount"), ["a.txt"]);
 
-    std::fs::read_to_string(full_path).unwrap()
+        unmount("nested/mount");
+    }
 }
@@ -0,0 +1,526 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use super::{normalize, Source};
+
+const ZIP_LOCAL_HEADER: u32 = 0x04034b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x02014b50;
+const ZIP_END_OF_DIRECTORY: u32 = 0x06054b50;
+
+const ZIP_STORED: u16 = 0;
+const ZIP_DEFLATED: u16 = 8;
+
+#[derive(Copy, Clone)]
+enum Compression {
+    None,
+    /// Stores the uncompressed size, so that a corrupt entry can't make it allocate
+    /// an unbounded amount of memory.
+    #[cfg(feature = "zip")]
+    Deflate {
+        size: u64,
+    },
+}
+
+struct Entry {
+    /// For zip files, this points at the local header rather than the data, as the
+    /// header's variable-length fields can differ from the central directory.
+    offset: u64,
+    compressed_size: u64,
+    compression: Compression,
+}
+
+/// A read-only archive, in either zip or Quake-style PAK format.
+///
+/// Only the index is kept in memory - files are read from disk when requested.
+pub(crate) struct Archive {
+    path: PathBuf,
+    is_zip: bool,
+    entries: HashMap<String, Entry>,
+}
+
+impl Archive {
+    pub fn open(path: &Path) -> io::Result<Archive> {
+        let mut file = File::open(path)?;
+
+        let mut magic = [0; 4];
+        file.read_exact(&mut magic)?;
+
+        let (is_zip, entries) = match &magic {
+            b"PACK" => (false, read_pak_index(&mut file)?),
+            b"PK\x03\x04" | b"PK\x05\x06" => (true, read_zip_index(&mut file)?),
+            _ => return Err(invalid_data("unrecognized archive format")),
+        };
+
+        Ok(Archive {
+            path: path.to_owned(),
+            is_zip,
+            entries,
+        })
+    }
+
+    fn data_offset(&self, file: &mut File, entry: &Entry) -> io::Result<u64> {
+        if !self.is_zip {
+            return Ok(entry.offset);
+        }
+
+        file.seek(SeekFrom::Start(entry.offset))?;
+
+        let mut header = [0; 30];
+        file.read_exact(&mut header)?;
+
+        if u32_at(&header, 0) != ZIP_LOCAL_HEADER {
+            return Err(invalid_data("invalid zip local header"));
+        }
+
+        let name_len = u16_at(&header, 26) as u64;
+        let extra_len = u16_at(&header, 28) as u64;
+
+        Ok(entry.offset + 30 + name_len + extra_len)
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        path.is_empty()
+            || self
+                .entries
+                .keys()
+                .any(|name| name.strip_prefix(path).is_some_and(|n| n.starts_with('/')))
+    }
+}
+
+impl Source for Archive {
+    fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>> {
+        let entry = self.entries.get(path)?;
+
+        let result = (|| {
+            let mut file = File::open(&self.path)?;
+
+            let offset = self.data_offset(&mut file, entry)?;
+            file.seek(SeekFrom::Start(offset))?;
+
+            let mut data = vec![0; entry.compressed_size as usize];
+            file.read_exact(&mut data)?;
+
+            match entry.compression {
+                Compression::None => Ok(data),
+                #[cfg(feature = "zip")]
+                Compression::Deflate { size } => {
+                    miniz_oxide::inflate::decompress_to_vec_with_limit(&data, size as usize)
+                        .map_err(|e| invalid_data(&format!("failed to decompress: {:?}", e)))
+                }
+            }
+        })();
+
+        Some(result)
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.entries.contains_key(path) || self.is_dir(path)
+    }
+
+    fn list(&self, dir: &str, out: &mut Vec<String>) {
+        for name in self.entries.keys() {
+            let rest = if dir.is_empty() {
+                Some(name.as_str())
+            } else {
+                name.strip_prefix(dir).and_then(|n| n.strip_prefix('/'))
+            };
+
+            if let Some(rest) = rest {
+                let child = rest.split('/').next().unwrap_or(rest);
+
+                if !child.is_empty() {
+                    out.push(child.to_owned());
+                }
+            }
+        }
+    }
+
+    fn real_path(&self, _path: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+fn read_pak_index(file: &mut File) -> io::Result<HashMap<String, Entry>> {
+    let file_len = file.seek(SeekFrom::End(0))?;
+
+    let mut header = [0; 8];
+    file.seek(SeekFrom::Start(4))?;
+    file.read_exact(&mut header)?;
+
+    let dir_offset = u32_at(&header, 0) as u64;
+    let dir_len = u32_at(&header, 4) as usize;
+
+    if !dir_len.is_multiple_of(64) || dir_offset + dir_len as u64 > file_len {
+        return Err(invalid_data("invalid pak directory"));
+    }
+
+    let mut dir = vec![0; dir_len];
+    file.seek(SeekFrom::Start(dir_offset))?;
+    file.read_exact(&mut dir)?;
+
+    let mut entries = HashMap::new();
+
+    for record in dir.chunks_exact(64) {
+        let name_len = record[..56].iter().position(|b| *b == 0).unwrap_or(56);
+        let name = String::from_utf8_lossy(&record[..name_len]);
+        let offset = u32_at(record, 56) as u64;
+        let size = u32_at(record, 60) as u64;
+
+        if offset + size > file_len {
+            return Err(invalid_data(&format!("invalid pak entry for {}", name)));
+        }
+
+        entries.insert(
+            normalize(&name),
+            Entry {
+                offset,
+                compressed_size: size,
+                compression: Compression::None,
+            },
+        );
+    }
+
+    Ok(entries)
+}
+
+fn read_zip_index(file: &mut File) -> io::Result<HashMap<String, Entry>> {
+    // The end of central directory record is at the end of the file, followed by a
+    // comment of up to 64KiB.
+    let file_len = file.seek(SeekFrom::End(0))?;
+    let search_len = file_len.min(22 + u16::MAX as u64);
+
+    let mut tail = vec![0; search_len as usize];
+    file.seek(SeekFrom::Start(file_len - search_len))?;
+    file.read_exact(&mut tail)?;
+
+    let eocd = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|i| u32_at(&tail, *i) == ZIP_END_OF_DIRECTORY)
+        .ok_or_else(|| invalid_data("missing zip end of central directory"))?;
+
+    let count = u16_at(&tail, eocd + 10) as usize;
+    let dir_len = u32_at(&tail, eocd + 12) as usize;
+    let dir_offset = u32_at(&tail, eocd + 16);
+
+    if dir_offset == u32::MAX {
+        return Err(invalid_data("zip64 archives are not supported"));
+    }
+
+    if dir_offset as u64 + dir_len as u64 > file_len {
+        return Err(invalid_data("invalid zip central directory"));
+    }
+
+    let mut dir = vec![0; dir_len];
+    file.seek(SeekFrom::Start(dir_offset as u64))?;
+    file.read_exact(&mut dir)?;
+
+    let mut entries = HashMap::new();
+    let mut pos = 0;
+
+    for _ in 0..count {
+        if pos + 46 > dir.len() || u32_at(&dir, pos) != ZIP_CENTRAL_HEADER {
+            return Err(invalid_data("invalid zip central directory"));
+        }
+
+        let method = u16_at(&dir, pos + 10);
+        let compressed_size = u32_at(&dir, pos + 20);
+        let size = u32_at(&dir, pos + 24);
+        let name_len = u16_at(&dir, pos + 28) as usize;
+        let extra_len = u16_at(&dir, pos + 30) as usize;
+        let comment_len = u16_at(&dir, pos + 32) as usize;
+        let offset = u32_at(&dir, pos + 42);
+
+        let name_start = pos + 46;
+        let name = dir
+            .get(name_start..name_start + name_len)
+            .ok_or_else(|| invalid_data("invalid zip central directory"))?;
+        let name = String::from_utf8_lossy(name);
+
+        pos = name_start + name_len + extra_len + comment_len;
+
+        // Directories are implied by the paths of the files inside them.
+        if name.ends_with('/') {
+            continue;
+        }
+
+        if compressed_size == u32::MAX || size == u32::MAX || offset == u32::MAX {
+            return Err(invalid_data("zip64 archives are not supported"));
+        }
+
+        if offset as u64 + compressed_size as u64 > file_len {
+            return Err(invalid_data(&format!("invalid zip entry for {}", name)));
+        }
+
+        let compression = match method {
+            ZIP_STORED => Compression::None,
+            #[cfg(feature = "zip")]
+            ZIP_DEFLATED => Compression::Deflate { size: size as u64 },
+            #[cfg(not(feature = "zip"))]
+            ZIP_DEFLATED => {
+                return Err(invalid_data(&format!(
+                    "{} is compressed with deflate, which requires the `zip` feature",
+                    name
+                )))
+            }
+            _ => {
+                return Err(invalid_data(&format!(
+                    "unsupported zip compression method {} for {}",
+                    method, name
+                )))
+            }
+        };
+
+        entries.insert(
+            normalize(&name),
+            Entry {
+                offset: offset as u64,
+                compressed_size: compressed_size as u64,
+                compression,
+            },
+        );
+    }
+
+    Ok(entries)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ZipFile<'a> {
+        name: &'a str,
+        method: u16,
+        data: Vec<u8>,
+        size: u32,
+    }
+
+    fn stored<'a>(name: &'a str, data: &[u8]) -> ZipFile<'a> {
+        ZipFile {
+            name,
+            method: ZIP_STORED,
+            data: data.to_vec(),
+            size: data.len() as u32,
+        }
+    }
+
+    fn zip(files: &[ZipFile<'_>]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut dir = Vec::new();
+
+        for file in files {
+            let offset = out.len() as u32;
+
+            out.extend(ZIP_LOCAL_HEADER.to_le_bytes());
+            out.extend([0; 4]);
+            out.extend(file.method.to_le_bytes());
+            out.extend([0; 8]);
+            out.extend((file.data.len() as u32).to_le_bytes());
+            out.extend(file.size.to_le_bytes());
+            out.extend((file.name.len() as u16).to_le_bytes());
+            out.extend([0; 2]);
+            out.extend(file.name.as_bytes());
+            out.extend(&file.data);
+
+            dir.extend(ZIP_CENTRAL_HEADER.to_le_bytes());
+            pad(&mut dir, 6);
+            dir.extend(file.method.to_le_bytes());
+            pad(&mut dir, 8);
+            dir.extend((file.data.len() as u32).to_le_bytes());
+            dir.extend(file.size.to_le_bytes());
+            dir.extend((file.name.len() as u16).to_le_bytes());
+            pad(&mut dir, 12);
+            dir.extend(offset.to_le_bytes());
+            dir.extend(file.name.as_bytes());
+        }
+
+        let dir_offset = out.len() as u32;
+        out.extend(&dir);
+
+        out.extend(ZIP_END_OF_DIRECTORY.to_le_bytes());
+        pad(&mut out, 6);
+        out.extend((files.len() as u16).to_le_bytes());
+        out.extend((dir.len() as u32).to_le_bytes());
+        out.extend(dir_offset.to_le_bytes());
+        pad(&mut out, 2);
+
+        out
+    }
+
+    fn pak(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+        let mut dir = Vec::new();
+
+        for (name, contents) in files {
+            let offset = 12 + data.len() as u32;
+            data.extend_from_slice(contents);
+
+            let mut record = [0; 64];
+            record[..name.len()].copy_from_slice(name.as_bytes());
+            record[56..60].copy_from_slice(&offset.to_le_bytes());
+            record[60..64].copy_from_slice(&(contents.len() as u32).to_le_bytes());
+            dir.extend(record);
+        }
+
+        let mut out = b"PACK".to_vec();
+        out.extend((12 + data.len() as u32).to_le_bytes());
+        out.extend((dir.len() as u32).to_le_bytes());
+        out.extend(data);
+        out.extend(dir);
+        out
+    }
+
+    fn pad(out: &mut Vec<u8>, len: usize) {
+        out.resize(out.len() + len, 0);
+    }
+
+    /// Archives are read lazily from disk, so the fixture is left in the temp directory.
+    fn open(name: &str, bytes: &[u8]) -> io::Result<Archive> {
+        let path = std::env::temp_dir().join(format!("nova-test-{}", name));
+        std::fs::write(&path, bytes).unwrap();
+
+        Archive::open(&path)
+    }
+
+    fn read(archive: &Archive, path: &str) -> io::Result<Vec<u8>> {
+        archive.read(path).expect("file should exist")
+    }
+
+    fn list(archive: &Archive, dir: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        archive.list(dir, &mut names);
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    #[test]
+    fn reads_pak() {
+        let archive = open(
+            "reads_pak.pak",
+            &pak(&[("a.txt", b"hello"), ("maps/b.txt", b"world")]),
+        )
+        .unwrap();
+
+        assert_eq!(read(&archive, "a.txt").unwrap(), b"hello");
+        assert_eq!(read(&archive, "maps/b.txt").unwrap(), b"world");
+        assert!(archive.read("c.txt").is_none());
+
+        assert!(archive.exists("maps"));
+        assert!(!archive.exists("map"));
+        assert_eq!(list(&archive, ""), ["a.txt", "maps"]);
+        assert_eq!(list(&archive, "maps"), ["b.txt"]);
+    }
+
+    #[test]
+    fn rejects_pak_directory_past_end() {
+        let mut bytes = pak(&[("a.txt", b"hello")]);
+        bytes[8..12].copy_from_slice(&(64 * 100u32).to_le_bytes());
+
+        assert!(open("pak_dir_past_end.pak", &bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_pak_entry_past_end() {
+        let mut bytes = pak(&[("a.txt", b"hello")]);
+        let size = bytes.len() - 4;
+        bytes[size..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(open("pak_entry_past_end.pak", &bytes).is_err());
+    }
+
+    #[test]
+    fn reads_stored_zip() {
+        let archive = open(
+            "reads_stored.zip",
+            &zip(&[stored("a.txt", b"hello"), stored("dir/b.txt", b"world")]),
+        )
+        .unwrap();
+
+        assert_eq!(read(&archive, "a.txt").unwrap(), b"hello");
+        assert_eq!(read(&archive, "dir/b.txt").unwrap(), b"world");
+        assert_eq!(list(&archive, ""), ["a.txt", "dir"]);
+    }
+
+    #[test]
+    fn rejects_zip_directory_past_end() {
+        let mut bytes = zip(&[stored("a.txt", b"hello")]);
+        let eocd = bytes.len() - 22;
+        bytes[eocd + 12..eocd + 16].copy_from_slice(&1000u32.to_le_bytes());
+
+        assert!(open("zip_dir_past_end.zip", &bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_compression() {
+        let mut file = stored("a.txt", b"hello");
+        file.method = 14;
+
+        assert!(open("unknown_compression.zip", &zip(&[file])).is_err());
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn reads_deflated_zip() {
+        let contents = b"hello hello hello hello";
+
+        let archive = open(
+            "reads_deflated.zip",
+            &zip(&[ZipFile {
+                name: "a.txt",
+                method: ZIP_DEFLATED,
+                data: miniz_oxide::deflate::compress_to_vec(contents, 6),
+                size: contents.len() as u32,
+            }]),
+        )
+        .unwrap();
+
+        assert_eq!(read(&archive, "a.txt").unwrap(), contents);
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn limits_decompressed_size() {
+        let contents = vec![0; 4096];
+
+        let archive = open(
+            "limits_decompressed.zip",
+            &zip(&[ZipFile {
+                name: "a.txt",
+                method: ZIP_DEFLATED,
+                data: miniz_oxide::deflate::compress_to_vec(&contents, 6),
+                size: 16,
+            }]),
+        )
+        .unwrap();
+
+        assert!(read(&archive, "a.txt").is_err());
+    }
+
+    #[cfg(not(feature = "zip"))]
+    #[test]
+    fn rejects_deflate_without_zip_feature() {
+        let mut file = stored("a.txt", b"hello");
+        file.method = ZIP_DEFLATED;
+
+        assert!(open("deflate_without_zip.zip", &zip(&[file])).is_err());
+    }
+}
@@ -8,6 +8,7 @@ mod scaling;
 mod shader;
 mod text;
 mod texture;
+mod transition;
 
 use std::rc::Rc;
 use std::{cell::Cell, sync::Arc};
@@ -24,6 +25,7 @@ pub use scaling::*;
 pub use shader::*;
 pub use text::*;
 pub use texture::*;
+pub use transition::*;
 
 use crate::window::Window;
 
@@ -101,13 +103,13 @@ impl Graphics {
             self.state.gl.bind_vertex_array(Some(self.state.vao));
             self.bind_vertex_buffer(Some(pass.mesh.raw.vertex_buffer));
             self.bind_index_buffer(Some(pass.mesh.raw.index_buffer));
-            self.bind_shader(Some(pass.shader.raw.id));
+            self.bind_shader(Some(pass.shader.raw.id.get()));
             self.bind_texture(Some(pass.texture.raw.id));
 
             let proj = self
                 .state
                 .gl
-                .get_uniform_location(pass.shader.raw.id, "u_projection")
+                .get_uniform_location(pass.shader.raw.id.get(), "u_projection")
                 .unwrap();
 
             let (target_width, target_height) = pass.target.size();
@@ -269,6 +271,7 @@ impl Target for Window {
     const FLIPPED: bool = false;
 
     fn bind(&self, gfx: &Graphics) {
+        self.make_current();
         gfx.bind_canvas(None);
 
         unsafe {
@@ -3,6 +3,7 @@ use std::rc::Rc;
 use glow::HasContext;
 
 use crate::graphics::{Graphics, Texture};
+use crate::window::Window;
 
 use super::RawTexture;
 
@@ -42,6 +43,45 @@ impl Canvas {
     pub fn into_texture(self) -> Texture {
         self.texture
     }
+
+    /// Copies the contents of the window's back buffer into the canvas, scaling it to
+    /// fit if the sizes don't match.
+    ///
+    /// This should be called after drawing and before presenting, as the contents of
+    /// the back buffer are undefined after the window has been presented.
+    pub fn copy_from_window(&self, window: &Window) {
+        let gfx = &self.raw.gfx;
+        let (window_width, window_height) = window.size();
+        let (width, height) = self.size();
+
+        window.make_current();
+
+        unsafe {
+            gfx.state.gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+            gfx.state
+                .gl
+                .bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(self.raw.id));
+
+            // The window's origin is at the bottom left, so the copy needs to be flipped
+            // to match the layout of the canvas.
+            gfx.state.gl.blit_framebuffer(
+                0,
+                0,
+                window_width as i32,
+                window_height as i32,
+                0,
+                height,
+                width,
+                0,
+                glow::COLOR_BUFFER_BIT,
+                glow::NEAREST,
+            );
+
+            gfx.state
+                .gl
+                .bind_framebuffer(glow::FRAMEBUFFER, gfx.state.current_canvas.get());
+        }
+    }
 }
 
 pub struct RawCanvas {
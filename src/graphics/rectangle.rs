@@ -1,7 +1,7 @@
 use glam::{IVec2, Vec2};
 
 macro_rules! rect {
-    ($rect:ident, $t:ty, $point:path, $zero:literal) => {
+    ($rect:ident, $t:ty, $point:path, $zero:literal, $two:literal) => {
         #[derive(Copy, Clone, Debug, PartialEq)]
         pub struct $rect {
             pub x: $t,
@@ -63,6 +63,10 @@ macro_rules! rect {
                 <$point>::new(self.x + self.width, self.y + self.height)
             }
 
+            pub fn center(&self) -> $point {
+                <$point>::new(self.x + self.width / $two, self.y + self.height / $two)
+            }
+
             pub fn intersects(&self, other: &$rect) -> bool {
                 self.x < other.x + other.width
                     && self.x + self.width > other.x
@@ -111,7 +115,7 @@ macro_rules! rect {
     };
 }
 
-rect!(Rectangle, f32, Vec2, 0.0);
+rect!(Rectangle, f32, Vec2, 0.0, 2.0);
 
 impl Rectangle {
     pub fn as_irectangle(&self) -> IRectangle {
@@ -124,7 +128,7 @@ impl Rectangle {
     }
 }
 
-rect!(IRectangle, i32, IVec2, 0);
+rect!(IRectangle, i32, IVec2, 0, 2);
 
 impl IRectangle {
     pub fn as_rectangle(&self) -> Rectangle {
@@ -1,3 +1,4 @@
+use std::cell::Cell;
 use std::rc::Rc;
 
 use glow::HasContext;
@@ -50,76 +51,108 @@ impl Shader {
 
         Shader { raw: Rc::new(raw) }
     }
+
+    /// Recompiles the shader from new source code. All handles to the shader will use
+    /// the new program.
+    ///
+    /// If compilation fails, the old program is kept and the error log is returned.
+    pub fn reload(&self, vertex_src: &str, fragment_src: &str) -> Result<(), String> {
+        self.raw.reload(vertex_src, fragment_src)
+    }
 }
 
 pub struct RawShader {
     state: Rc<State>,
-    pub(crate) id: glow::Program,
+    pub(crate) id: Cell<glow::Program>,
 }
 
 impl RawShader {
     pub fn new(gfx: &Graphics, vertex_src: &str, fragment_src: &str) -> RawShader {
-        unsafe {
-            let program = gfx.state.gl.create_program().unwrap();
+        let program = compile(gfx, vertex_src, fragment_src).unwrap_or_else(|e| panic!("{}", e));
 
-            gfx.state.gl.bind_attrib_location(program, 0, "a_pos");
-            gfx.state.gl.bind_attrib_location(program, 1, "a_uv");
+        RawShader {
+            state: Rc::clone(&gfx.state),
+            id: Cell::new(program),
+        }
+    }
 
-            let vertex_shader = gfx.state.gl.create_shader(glow::VERTEX_SHADER).unwrap();
+    pub fn reload(&self, vertex_src: &str, fragment_src: &str) -> Result<(), String> {
+        let gfx = Graphics {
+            state: Rc::clone(&self.state),
+        };
 
-            gfx.state.gl.shader_source(vertex_shader, vertex_src);
-            gfx.state.gl.compile_shader(vertex_shader);
-            gfx.state.gl.attach_shader(program, vertex_shader);
+        let program = compile(&gfx, vertex_src, fragment_src)?;
+        let old = self.id.replace(program);
 
-            if !gfx.state.gl.get_shader_compile_status(vertex_shader) {
-                panic!("{}", gfx.state.gl.get_shader_info_log(vertex_shader));
-            }
+        unsafe {
+            self.state.gl.delete_program(old);
+        }
 
-            let fragment_shader = gfx.state.gl.create_shader(glow::FRAGMENT_SHADER).unwrap();
+        // Compiling binds the new program, so there's no need to clear the old one.
+        Ok(())
+    }
+}
 
-            gfx.state.gl.shader_source(fragment_shader, fragment_src);
-            gfx.state.gl.compile_shader(fragment_shader);
-            gfx.state.gl.attach_shader(program, fragment_shader);
+impl Drop for RawShader {
+    fn drop(&mut self) {
+        unsafe {
+            self.state.gl.delete_program(self.id.get());
 
-            if !gfx.state.gl.get_shader_compile_status(fragment_shader) {
-                panic!("{}", gfx.state.gl.get_shader_info_log(fragment_shader));
+            if self.state.current_shader.get() == Some(self.id.get()) {
+                self.state.current_shader.set(None);
             }
+        }
+    }
+}
 
-            gfx.state.gl.link_program(program);
+fn compile(gfx: &Graphics, vertex_src: &str, fragment_src: &str) -> Result<glow::Program, String> {
+    unsafe {
+        let program = gfx.state.gl.create_program()?;
 
-            if !gfx.state.gl.get_program_link_status(program) {
-                panic!("{}", gfx.state.gl.get_program_info_log(program));
-            }
+        gfx.state.gl.bind_attrib_location(program, 0, "a_pos");
+        gfx.state.gl.bind_attrib_location(program, 1, "a_uv");
 
-            gfx.state.gl.delete_shader(vertex_shader);
-            gfx.state.gl.delete_shader(fragment_shader);
+        let vertex_shader = gfx.state.gl.create_shader(glow::VERTEX_SHADER)?;
 
-            gfx.bind_shader(Some(program));
+        gfx.state.gl.shader_source(vertex_shader, vertex_src);
+        gfx.state.gl.compile_shader(vertex_shader);
+        gfx.state.gl.attach_shader(program, vertex_shader);
 
-            let sampler = gfx
-                .state
-                .gl
-                .get_uniform_location(program, "u_texture")
-                .unwrap();
+        let fragment_shader = gfx.state.gl.create_shader(glow::FRAGMENT_SHADER)?;
 
-            gfx.state.gl.uniform_1_i32(Some(&sampler), 0);
+        gfx.state.gl.shader_source(fragment_shader, fragment_src);
+        gfx.state.gl.compile_shader(fragment_shader);
+        gfx.state.gl.attach_shader(program, fragment_shader);
+
+        let result = if !gfx.state.gl.get_shader_compile_status(vertex_shader) {
+            Err(gfx.state.gl.get_shader_info_log(vertex_shader))
+        } else if !gfx.state.gl.get_shader_compile_status(fragment_shader) {
+            Err(gfx.state.gl.get_shader_info_log(fragment_shader))
+        } else {
+            gfx.state.gl.link_program(program);
 
-            RawShader {
-                state: Rc::clone(&gfx.state),
-                id: program,
+            if !gfx.state.gl.get_program_link_status(program) {
+                Err(gfx.state.gl.get_program_info_log(program))
+            } else {
+                Ok(())
             }
+        };
+
+        gfx.state.gl.delete_shader(vertex_shader);
+        gfx.state.gl.delete_shader(fragment_shader);
+
+        if let Err(e) = result {
+            gfx.state.gl.delete_program(program);
+            return Err(e);
         }
-    }
-}
 
-impl Drop for RawShader {
-    fn drop(&mut self) {
-        unsafe {
-            self.state.gl.delete_program(self.id);
+        gfx.bind_shader(Some(program));
 
-            if self.state.current_shader.get() == Some(self.id) {
-                self.state.current_shader.set(None);
-            }
+        // Shaders that don't sample a texture can optimize the uniform away.
+        if let Some(sampler) = gfx.state.gl.get_uniform_location(program, "u_texture") {
+            gfx.state.gl.uniform_1_i32(Some(&sampler), 0);
         }
+
+        Ok(program)
     }
 }
@@ -16,14 +16,25 @@ pub struct Font {
 
 impl Font {
     pub fn from_file(path: &str) -> Font {
-        let bytes = fs::read(path);
-        Font::from_data(&bytes)
+        Font::try_from_file(path).unwrap()
+    }
+
+    /// Like `from_file`, but returns an error instead of panicking if the file can't be
+    /// read or parsed.
+    pub fn try_from_file(path: &str) -> Result<Font, String> {
+        let bytes = fs::try_read(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+
+        Font::try_from_data(&bytes).map_err(|e| format!("failed to parse {}: {}", path, e))
     }
 
     pub fn from_data(data: &[u8]) -> Font {
-        Font {
-            data: FontdueFont::from_bytes(data, FontSettings::default()).unwrap(),
-        }
+        Font::try_from_data(data).unwrap()
+    }
+
+    pub fn try_from_data(data: &[u8]) -> Result<Font, String> {
+        let data = FontdueFont::from_bytes(data, FontSettings::default())?;
+
+        Ok(Font { data })
     }
 }
 
@@ -42,6 +53,9 @@ pub struct SpriteFont {
     pub descent: f32,
     pub line_gap: f32,
 
+    size: f32,
+    scale: f32,
+
     texture: Texture,
     cache: HashMap<char, SpriteFontGlyph>,
     kerning: HashMap<(char, char), f32>,
@@ -49,6 +63,17 @@ pub struct SpriteFont {
 
 impl SpriteFont {
     pub fn new(gfx: &Graphics, font: &Font, size: f32) -> SpriteFont {
+        SpriteFont::with_scale(gfx, font, size, 1.0)
+    }
+
+    /// Creates a sprite font that is rasterized at `size * scale` pixels. This should be
+    /// used with `Window::display_scale` to get crisp text on high-DPI displays.
+    ///
+    /// All of the font's metrics will be in pixels.
+    pub fn with_scale(gfx: &Graphics, font: &Font, size: f32, scale: f32) -> SpriteFont {
+        let unscaled_size = size;
+        let size = size * scale;
+
         // TODO: Refactor to pack then allocate
         let mut packer = ShelfPacker::new(gfx, 256, 256);
         let mut cache = HashMap::new();
@@ -106,12 +131,29 @@ impl SpriteFont {
             descent: line_metrics.descent,
             line_gap: line_metrics.line_gap,
 
+            size: unscaled_size,
+            scale,
+
             texture: packer.into_texture(),
             cache,
             kerning,
         }
     }
 
+    /// Re-rasterizes the font at a new scale, e.g. after receiving a
+    /// `WindowDisplayScaleChanged` event.
+    pub fn rebuild(&mut self, gfx: &Graphics, font: &Font, scale: f32) {
+        *self = SpriteFont::with_scale(gfx, font, self.size, scale);
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
     pub fn texture(&self) -> &Texture {
         &self.texture
     }
@@ -127,6 +169,41 @@ impl SpriteFont {
     pub fn kerning(&self, a: char, b: char) -> Option<f32> {
         self.kerning.get(&(a, b)).copied()
     }
+
+    /// Measures the size of the given text, using the same layout rules as `Batcher::text`.
+    pub fn measure(&self, text: &str) -> Vec2 {
+        let mut width: f32 = 0.0;
+        let mut cursor_x = 0.0;
+        let mut lines = 1;
+        let mut last_char = None;
+
+        for ch in text.chars() {
+            if ch.is_control() {
+                if ch == '\n' {
+                    width = width.max(cursor_x);
+                    cursor_x = 0.0;
+                    lines += 1;
+                }
+
+                continue;
+            }
+
+            if let Some(glyph) = self.glyph(ch) {
+                if let Some(kerning) = last_char.and_then(|l| self.kerning(l, ch)) {
+                    cursor_x += kerning;
+                }
+
+                cursor_x += glyph.advance;
+
+                last_char = Some(ch);
+            }
+        }
+
+        Vec2::new(
+            width.max(cursor_x),
+            lines as f32 * self.line_height().floor(),
+        )
+    }
 }
 
 pub struct TextSegment<'a> {
@@ -1,3 +1,4 @@
+use std::cell::Cell;
 use std::rc::Rc;
 
 use glow::{HasContext, PixelUnpackData};
@@ -13,33 +14,13 @@ pub struct Texture {
 
 impl Texture {
     pub fn from_file(gfx: &Graphics, path: &str, premultiply: bool) -> Texture {
-        let bytes = fs::read(path);
+        let image = ImageData::from_file(path, premultiply);
 
-        let decoder = Decoder::new(bytes.as_slice());
-        let mut reader = decoder.read_info().unwrap();
-        let mut buf = vec![0; reader.output_buffer_size()];
-        let info = reader.next_frame(&mut buf).unwrap();
-
-        assert!(info.color_type == ColorType::Rgba);
-        assert!(info.bit_depth == BitDepth::Eight);
-
-        if premultiply {
-            for pixel in buf.chunks_mut(4) {
-                let a = pixel[3];
-
-                if a == 0 {
-                    pixel[0] = 0;
-                    pixel[1] = 0;
-                    pixel[2] = 0;
-                } else if a < 255 {
-                    pixel[0] = ((pixel[0] as u16 * a as u16) >> 8) as u8;
-                    pixel[1] = ((pixel[1] as u16 * a as u16) >> 8) as u8;
-                    pixel[2] = ((pixel[2] as u16 * a as u16) >> 8) as u8;
-                }
-            }
-        }
+        Texture::from_image(gfx, &image)
+    }
 
-        Texture::from_data(gfx, info.width as i32, info.height as i32, &buf)
+    pub fn from_image(gfx: &Graphics, image: &ImageData) -> Texture {
+        Texture::from_data(gfx, image.width, image.height, &image.pixels)
     }
 
     pub fn from_data(gfx: &Graphics, width: i32, height: i32, data: &[u8]) -> Texture {
@@ -58,20 +39,32 @@ impl Texture {
     }
 
     pub fn width(&self) -> i32 {
-        self.raw.width
+        self.raw.width.get()
     }
 
     pub fn height(&self) -> i32 {
-        self.raw.height
+        self.raw.height.get()
     }
 
     pub fn size(&self) -> (i32, i32) {
-        (self.raw.width, self.raw.height)
+        (self.raw.width.get(), self.raw.height.get())
     }
 
     pub fn set_data(&self, data: &[u8]) {
         self.raw
-            .set_region(0, 0, self.raw.width, self.raw.height, data);
+            .set_region(0, 0, self.raw.width.get(), self.raw.height.get(), data);
+    }
+
+    /// Replaces the contents of the texture with an image, resizing it if needed. All
+    /// handles to the texture will see the new contents.
+    ///
+    /// This should not be used to resize a canvas's texture.
+    pub fn set_image(&self, image: &ImageData) {
+        if self.size() == (image.width, image.height) {
+            self.set_data(&image.pixels);
+        } else {
+            self.raw.resize(image.width, image.height, &image.pixels);
+        }
     }
 
     pub fn set_region(&self, x: i32, y: i32, width: i32, height: i32, data: &[u8]) {
@@ -79,6 +72,71 @@ impl Texture {
     }
 }
 
+/// Decoded RGBA image data, which has not yet been uploaded to the GPU.
+#[derive(Clone)]
+pub struct ImageData {
+    pub width: i32,
+    pub height: i32,
+    pub pixels: Vec<u8>,
+}
+
+impl ImageData {
+    pub fn from_file(path: &str, premultiply: bool) -> ImageData {
+        ImageData::try_from_file(path, premultiply).unwrap()
+    }
+
+    /// Like `from_file`, but returns an error instead of panicking if the file can't be
+    /// read or decoded.
+    pub fn try_from_file(path: &str, premultiply: bool) -> Result<ImageData, String> {
+        let bytes = fs::try_read(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+
+        ImageData::try_from_png(&bytes, premultiply)
+            .map_err(|e| format!("failed to decode {}: {}", path, e))
+    }
+
+    pub fn from_png(bytes: &[u8], premultiply: bool) -> ImageData {
+        ImageData::try_from_png(bytes, premultiply).unwrap()
+    }
+
+    /// Like `from_png`, but returns an error instead of panicking if the data is not a
+    /// valid 8-bit RGBA PNG.
+    pub fn try_from_png(bytes: &[u8], premultiply: bool) -> Result<ImageData, String> {
+        let decoder = Decoder::new(bytes);
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+
+        if info.color_type != ColorType::Rgba || info.bit_depth != BitDepth::Eight {
+            return Err(format!(
+                "expected 8-bit RGBA, found {:?} {:?}",
+                info.bit_depth, info.color_type
+            ));
+        }
+
+        if premultiply {
+            for pixel in buf.chunks_mut(4) {
+                let a = pixel[3];
+
+                if a == 0 {
+                    pixel[0] = 0;
+                    pixel[1] = 0;
+                    pixel[2] = 0;
+                } else if a < 255 {
+                    pixel[0] = ((pixel[0] as u16 * a as u16) >> 8) as u8;
+                    pixel[1] = ((pixel[1] as u16 * a as u16) >> 8) as u8;
+                    pixel[2] = ((pixel[2] as u16 * a as u16) >> 8) as u8;
+                }
+            }
+        }
+
+        Ok(ImageData {
+            width: info.width as i32,
+            height: info.height as i32,
+            pixels: buf,
+        })
+    }
+}
+
 impl PartialEq for Texture {
     fn eq(&self, other: &Self) -> bool {
         self.raw.id == other.raw.id
@@ -88,8 +146,8 @@ impl PartialEq for Texture {
 pub struct RawTexture {
     gfx: Graphics,
     pub(crate) id: glow::Texture,
-    width: i32,
-    height: i32,
+    width: Cell<i32>,
+    height: Cell<i32>,
 }
 
 impl RawTexture {
@@ -148,8 +206,8 @@ impl RawTexture {
             RawTexture {
                 gfx: gfx.clone(),
                 id,
-                width,
-                height,
+                width: Cell::new(width),
+                height: Cell::new(height),
             }
         }
     }
@@ -157,7 +215,12 @@ impl RawTexture {
     pub fn set_region(&self, x: i32, y: i32, width: i32, height: i32, data: &[u8]) {
         unsafe {
             assert_eq!(width as usize * height as usize * 4, data.len());
-            assert!(x >= 0 && y >= 0 && x + width <= self.width && y + height <= self.height);
+            assert!(
+                x >= 0
+                    && y >= 0
+                    && x + width <= self.width.get()
+                    && y + height <= self.height.get()
+            );
 
             self.gfx.bind_texture(Some(self.id));
 
@@ -174,6 +237,29 @@ impl RawTexture {
             )
         }
     }
+
+    pub fn resize(&self, width: i32, height: i32, data: &[u8]) {
+        unsafe {
+            assert_eq!(width as usize * height as usize * 4, data.len());
+
+            self.gfx.bind_texture(Some(self.id));
+
+            self.gfx.state.gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                glow::RGBA8 as i32,
+                width,
+                height,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                PixelUnpackData::Slice(Some(data)),
+            );
+
+            self.width.set(width);
+            self.height.set(height);
+        }
+    }
 }
 
 impl Drop for RawTexture {
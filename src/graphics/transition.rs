@@ -0,0 +1,222 @@
+use glam::Vec2;
+
+use crate::graphics::{Batcher, Canvas, Color, DrawParams, Graphics, Rectangle, Target};
+use crate::input::Direction;
+
+/// The number of horizontal strips used to approximate the circle in an iris transition.
+const IRIS_STRIPS: usize = 256;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TransitionKind {
+    /// Fades the outgoing frame out to a color, then fades the incoming frame in.
+    Fade(Color),
+
+    /// Blends directly from the outgoing frame to the incoming frame.
+    Crossfade,
+
+    /// Slides the edge of the incoming frame across the outgoing frame, moving in
+    /// the given direction.
+    Wipe(Direction),
+
+    /// Shrinks a circle around the outgoing frame down to a color, then grows a circle
+    /// around the incoming frame.
+    Iris(Color),
+
+    /// Pixelates the outgoing frame up to the given block size, then switches to the
+    /// incoming frame and reverses the effect.
+    Pixelate(u32),
+}
+
+/// An animated transition between two frames.
+///
+/// The outgoing and incoming frames should be drawn into `from_canvas` and `to_canvas`
+/// (or copied in via `Canvas::copy_from_window`), and then `draw` will blend them
+/// together. The transition's progress is measured in ticks, and is advanced by calling
+/// `update` from `EventHandler::update`, or `advance` if the game doesn't use fixed
+/// updates.
+pub struct Transition {
+    gfx: Graphics,
+    kind: TransitionKind,
+    duration: u32,
+    elapsed: f32,
+
+    from: Canvas,
+    to: Canvas,
+    scratch: Option<Canvas>,
+}
+
+impl Transition {
+    /// Creates a transition which will take `duration` ticks to complete. The canvases
+    /// will be created at the given size, which should usually match the size of the
+    /// target being drawn to.
+    pub fn new(
+        gfx: &Graphics,
+        kind: TransitionKind,
+        duration: u32,
+        width: i32,
+        height: i32,
+    ) -> Transition {
+        Transition {
+            gfx: gfx.clone(),
+            kind,
+            duration: duration.max(1),
+            elapsed: 0.0,
+
+            from: Canvas::new(gfx, width, height),
+            to: Canvas::new(gfx, width, height),
+            scratch: None,
+        }
+    }
+
+    pub fn kind(&self) -> TransitionKind {
+        self.kind
+    }
+
+    /// The canvas that the outgoing frame should be drawn to.
+    pub fn from_canvas(&self) -> &Canvas {
+        &self.from
+    }
+
+    /// The canvas that the incoming frame should be drawn to.
+    pub fn to_canvas(&self) -> &Canvas {
+        &self.to
+    }
+
+    /// Advances the transition by one tick.
+    pub fn update(&mut self) {
+        self.advance(1.0);
+    }
+
+    /// Advances the transition by a number of ticks, which can be fractional.
+    pub fn advance(&mut self, ticks: f32) {
+        self.elapsed = f32::min(self.elapsed + ticks, self.duration as f32);
+    }
+
+    /// Returns how far through the transition is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        self.elapsed / self.duration as f32
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration as f32
+    }
+
+    /// Draws the current state of the transition to the target.
+    ///
+    /// `blend_factor` is used to smooth out the animation between ticks, and should
+    /// usually be `Timer::blend_factor`.
+    ///
+    /// This flushes the batch, so any sprites that have already been added to it will
+    /// be drawn to the target first.
+    pub fn draw(&mut self, batch: &mut Batcher, target: &impl Target, blend_factor: f32) {
+        batch.draw(target);
+
+        let t = if self.is_finished() {
+            1.0
+        } else {
+            ((self.elapsed + blend_factor) / self.duration as f32).clamp(0.0, 1.0)
+        };
+
+        let (width, height) = self.from.size();
+        let bounds = Rectangle::new(0.0, 0.0, width as f32, height as f32);
+
+        match self.kind {
+            TransitionKind::Fade(color) => {
+                let (frame, amount) = self.half(t);
+
+                batch.texture(frame.texture(), Vec2::ZERO, DrawParams::new());
+                batch.rect(bounds, DrawParams::new().color(color * amount));
+            }
+
+            TransitionKind::Crossfade => {
+                batch.texture(self.from.texture(), Vec2::ZERO, DrawParams::new());
+                batch.texture(
+                    self.to.texture(),
+                    Vec2::ZERO,
+                    DrawParams::new().color(Color::WHITE * t),
+                );
+            }
+
+            TransitionKind::Wipe(direction) => {
+                batch.texture(self.from.texture(), Vec2::ZERO, DrawParams::new());
+
+                let w = bounds.width * t;
+                let h = bounds.height * t;
+
+                let revealed = match direction {
+                    Direction::Right => Rectangle::new(0.0, 0.0, w, bounds.height),
+                    Direction::Left => Rectangle::new(bounds.width - w, 0.0, w, bounds.height),
+                    Direction::Down => Rectangle::new(0.0, 0.0, bounds.width, h),
+                    Direction::Up => Rectangle::new(0.0, bounds.height - h, bounds.width, h),
+                };
+
+                batch.texture_dest(self.to.texture(), revealed, revealed, DrawParams::new());
+            }
+
+            TransitionKind::Iris(color) => {
+                let (frame, amount) = self.half(t);
+
+                let center = Vec2::new(bounds.width, bounds.height) / 2.0;
+                let radius = center.length() * (1.0 - amount);
+
+                batch.rect(bounds, DrawParams::new().color(color));
+
+                let strip_height = bounds.height / IRIS_STRIPS as f32;
+
+                for i in 0..IRIS_STRIPS {
+                    let y = i as f32 * strip_height;
+                    let dy = (y + strip_height / 2.0 - center.y).abs();
+
+                    if dy >= radius {
+                        continue;
+                    }
+
+                    let half_width = (radius * radius - dy * dy).sqrt();
+                    let x = (center.x - half_width).max(0.0);
+                    let right = (center.x + half_width).min(bounds.width);
+
+                    let strip = Rectangle::new(x, y, right - x, strip_height);
+
+                    batch.texture_dest(frame.texture(), strip, strip, DrawParams::new());
+                }
+            }
+
+            TransitionKind::Pixelate(max_block_size) => {
+                let (frame, amount) = self.half(t);
+                let frame = frame.clone();
+
+                let block_size = 1.0 + (max_block_size.max(1) - 1) as f32 * amount;
+                let small = Rectangle::new(
+                    0.0,
+                    0.0,
+                    (bounds.width / block_size).ceil(),
+                    (bounds.height / block_size).ceil(),
+                );
+
+                let scratch = self
+                    .scratch
+                    .get_or_insert_with(|| Canvas::new(&self.gfx, width, height));
+
+                // Shrink the frame into the corner of the scratch canvas, and then
+                // stretch it back out. Textures use nearest neighbour filtering, so
+                // this gives a blocky effect.
+                batch.texture_dest(frame.texture(), bounds, small, DrawParams::new());
+                batch.draw(scratch);
+
+                batch.texture_dest(scratch.texture(), small, bounds, DrawParams::new());
+            }
+        }
+
+        batch.draw(target);
+    }
+
+    /// For transitions that go out and then back in, returns which frame should be
+    /// shown, and how much of it should be covered (0.0 to 1.0).
+    fn half(&self, t: f32) -> (&Canvas, f32) {
+        if t < 0.5 {
+            (&self.from, t * 2.0)
+        } else {
+            (&self.to, (1.0 - t) * 2.0)
+        }
+    }
+}
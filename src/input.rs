@@ -2,16 +2,26 @@ mod event;
 mod gamepad;
 mod key;
 mod mouse;
+mod navigation;
+mod text_edit;
+mod user_event;
+mod virtual_cursor;
 
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 
 use glam::Vec2;
 
+use crate::window::Window;
+
 pub use self::event::*;
 pub use self::gamepad::*;
 pub use self::key::*;
 pub use self::mouse::*;
+pub use self::navigation::*;
+pub use self::text_edit::*;
+pub use self::user_event::*;
+pub use self::virtual_cursor::*;
 
 pub struct Input {
     keys: ButtonState<Key>,
@@ -20,6 +30,7 @@ pub struct Input {
 
     axes: AxisState,
     mouse_position: Vec2,
+    mouse_delta: Vec2,
 
     gamepads: Vec<Option<Gamepad>>,
     joystick_ids: HashMap<JoystickID, usize>,
@@ -34,6 +45,7 @@ impl Input {
 
             axes: AxisState::new(),
             mouse_position: Vec2::ZERO,
+            mouse_delta: Vec2::ZERO,
 
             gamepads: Vec::new(),
             joystick_ids: HashMap::new(),
@@ -42,11 +54,18 @@ impl Input {
 
     pub fn event(&mut self, event: &Event) {
         match event {
-            Event::KeyDown(key) => self.keys.set_down(*key),
-            Event::KeyUp(key) => self.keys.set_up(*key),
-            Event::MouseButtonDown(button) => self.mouse_buttons.set_down(*button),
-            Event::MouseButtonUp(button) => self.mouse_buttons.set_up(*button),
-            Event::MouseMotion { new_position } => self.mouse_position = *new_position,
+            Event::KeyDown { key, .. } => self.keys.set_down(*key),
+            Event::KeyUp { key, .. } => self.keys.set_up(*key),
+            Event::MouseButtonDown { button, .. } => self.mouse_buttons.set_down(*button),
+            Event::MouseButtonUp { button, .. } => self.mouse_buttons.set_up(*button),
+            Event::MouseMotion {
+                new_position,
+                delta,
+                ..
+            } => {
+                self.mouse_position = *new_position;
+                self.mouse_delta += *delta;
+            }
 
             Event::ControllerDeviceAdded { joystick, gamepad } => {
                 let empty_slot = self.gamepads.iter().position(Option::is_none);
@@ -102,6 +121,7 @@ impl Input {
         self.mouse_buttons.clear();
         self.gamepad_buttons.clear();
         self.axes.clear();
+        self.mouse_delta = Vec2::ZERO;
     }
 
     pub fn is_key_down(&self, key: Key) -> bool {
@@ -136,10 +156,24 @@ impl Input {
         self.mouse_buttons.is_released(button)
     }
 
+    /// Returns the mouse position in logical coordinates, relative to the main window.
     pub fn mouse_position(&self) -> Vec2 {
         self.mouse_position
     }
 
+    /// Returns how far the mouse has moved since the last update, in logical coordinates.
+    ///
+    /// Unlike the position, this keeps changing in relative mouse mode.
+    pub fn mouse_delta(&self) -> Vec2 {
+        self.mouse_delta
+    }
+
+    /// Returns the mouse position in pixels, for comparing against things drawn
+    /// to the window.
+    pub fn mouse_pixel_position(&self, window: &Window) -> Vec2 {
+        window.to_pixels(self.mouse_position)
+    }
+
     pub fn is_gamepad_button_down(&self, player: usize, button: GamepadButton) -> bool {
         self.gamepad_buttons.is_down((player, button))
     }
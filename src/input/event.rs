@@ -1,19 +1,51 @@
+use std::path::PathBuf;
+
 use glam::Vec2;
 use sdl3_sys::events::*;
 use sdl3_sys::gamepad::*;
+use sdl3_sys::video::*;
+
+use crate::window::{self, DialogID, Display, FileDialogResult, WindowID};
 
-use super::{Gamepad, GamepadAxis, GamepadButton, JoystickID, Key, MouseButton};
+use super::{
+    take_user_event, user_event_type, Gamepad, GamepadAxis, GamepadButton, JoystickID, Key,
+    MouseButton, UserEvent,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     Quit,
-    KeyDown(Key),
-    KeyUp(Key),
-    MouseButtonDown(MouseButton),
-    MouseButtonUp(MouseButton),
 
+    KeyDown {
+        window: WindowID,
+        key: Key,
+    },
+
+    KeyUp {
+        window: WindowID,
+        key: Key,
+    },
+
+    KeyRepeat {
+        window: WindowID,
+        key: Key,
+    },
+
+    MouseButtonDown {
+        window: WindowID,
+        button: MouseButton,
+    },
+
+    MouseButtonUp {
+        window: WindowID,
+        button: MouseButton,
+    },
+
+    /// The position is in the logical coordinates of the window that the mouse is over.
     MouseMotion {
+        window: WindowID,
         new_position: Vec2,
+        delta: Vec2,
     },
 
     ControllerDeviceAdded {
@@ -42,18 +74,135 @@ pub enum Event {
     },
 
     WindowResized {
+        window: WindowID,
+        width: u32,
+        height: u32,
+    },
+
+    WindowPixelSizeChanged {
+        window: WindowID,
         width: u32,
         height: u32,
     },
 
+    WindowDisplayScaleChanged {
+        window: WindowID,
+        scale: f32,
+    },
+
+    WindowMoved {
+        window: WindowID,
+        x: i32,
+        y: i32,
+    },
+
+    WindowFocusGained {
+        window: WindowID,
+    },
+
+    WindowFocusLost {
+        window: WindowID,
+    },
+
+    WindowMinimized {
+        window: WindowID,
+    },
+
+    WindowMaximized {
+        window: WindowID,
+    },
+
+    WindowRestored {
+        window: WindowID,
+    },
+
+    /// The user has tried to close the window. If this is the app's main window, the
+    /// app will stop running afterwards, unless `App::cancel_quit` is called while
+    /// handling this event.
+    WindowCloseRequested {
+        window: WindowID,
+    },
+
+    FileDropped {
+        window: WindowID,
+        path: PathBuf,
+    },
+
+    /// A file dialog has been closed.
+    FileDialog {
+        dialog: DialogID,
+        result: FileDialogResult,
+    },
+
+    TextDropped {
+        window: WindowID,
+        text: String,
+    },
+
     TextInput {
+        window: WindowID,
         text: String,
     },
+
+    ClipboardUpdated,
+
+    DisplayAdded {
+        display: Display,
+    },
+
+    DisplayRemoved {
+        display: Display,
+    },
+
+    /// The display's mode, orientation, position or content scale has changed.
+    DisplayChanged {
+        display: Display,
+    },
+
+    /// A message sent via an `EventSender`.
+    User(UserEvent),
 }
 
 impl Event {
+    /// Returns the window that the event belongs to, if it is specific to one window.
+    pub fn window(&self) -> Option<WindowID> {
+        match self {
+            Event::KeyDown { window, .. }
+            | Event::KeyUp { window, .. }
+            | Event::KeyRepeat { window, .. }
+            | Event::MouseButtonDown { window, .. }
+            | Event::MouseButtonUp { window, .. }
+            | Event::MouseMotion { window, .. }
+            | Event::WindowResized { window, .. }
+            | Event::WindowPixelSizeChanged { window, .. }
+            | Event::WindowDisplayScaleChanged { window, .. }
+            | Event::WindowMoved { window, .. }
+            | Event::WindowFocusGained { window }
+            | Event::WindowFocusLost { window }
+            | Event::WindowMinimized { window }
+            | Event::WindowMaximized { window }
+            | Event::WindowRestored { window }
+            | Event::WindowCloseRequested { window }
+            | Event::FileDropped { window, .. }
+            | Event::TextDropped { window, .. }
+            | Event::TextInput { window, .. } => Some(*window),
+
+            _ => None,
+        }
+    }
+
     pub fn from_raw(event: &SDL_Event) -> Option<Event> {
         unsafe {
+            if event.r#type == window::dialog_event_type() {
+                let (dialog, result) = window::take_dialog_result(&event.user);
+
+                return Some(Event::FileDialog { dialog, result });
+            }
+
+            if event.r#type == user_event_type() {
+                return Some(Event::User(take_user_event(&event.user)));
+            }
+
             match SDL_EventType(event.r#type) {
                 SDL_EVENT_QUIT => {
                     return Some(Event::Quit);
@@ -61,31 +210,54 @@ impl Event {
 
                 SDL_EVENT_KEY_DOWN if !event.key.repeat => {
                     if let Some(key) = Key::from_raw(event.key.scancode) {
-                        return Some(Event::KeyDown(key));
+                        return Some(Event::KeyDown {
+                            window: WindowID::from_raw(event.key.windowID),
+                            key,
+                        });
+                    }
+                }
+
+                SDL_EVENT_KEY_DOWN => {
+                    if let Some(key) = Key::from_raw(event.key.scancode) {
+                        return Some(Event::KeyRepeat {
+                            window: WindowID::from_raw(event.key.windowID),
+                            key,
+                        });
                     }
                 }
 
                 SDL_EVENT_KEY_UP if !event.key.repeat => {
                     if let Some(key) = Key::from_raw(event.key.scancode) {
-                        return Some(Event::KeyUp(key));
+                        return Some(Event::KeyUp {
+                            window: WindowID::from_raw(event.key.windowID),
+                            key,
+                        });
                     }
                 }
 
                 SDL_EVENT_MOUSE_BUTTON_DOWN => {
                     if let Some(button) = MouseButton::from_raw(event.button.button as i32) {
-                        return Some(Event::MouseButtonDown(button));
+                        return Some(Event::MouseButtonDown {
+                            window: WindowID::from_raw(event.button.windowID),
+                            button,
+                        });
                     }
                 }
 
                 SDL_EVENT_MOUSE_BUTTON_UP => {
                     if let Some(button) = MouseButton::from_raw(event.button.button as i32) {
-                        return Some(Event::MouseButtonUp(button));
+                        return Some(Event::MouseButtonUp {
+                            window: WindowID::from_raw(event.button.windowID),
+                            button,
+                        });
                     }
                 }
 
                 SDL_EVENT_MOUSE_MOTION => {
                     return Some(Event::MouseMotion {
+                        window: WindowID::from_raw(event.motion.windowID),
                         new_position: Vec2::new(event.motion.x, event.motion.y),
+                        delta: Vec2::new(event.motion.xrel, event.motion.yrel),
                     });
                 }
 
@@ -158,16 +330,139 @@ impl Event {
                     if e.data1 > 0 && e.data2 > 0 {
                         let width = e.data1 as u32;
                         let height = e.data2 as u32;
-                        return Some(Event::WindowResized { width, height });
+                        return Some(Event::WindowResized {
+                            window: WindowID::from_raw(e.windowID),
+                            width,
+                            height,
+                        });
+                    }
+                }
+
+                SDL_EVENT_WINDOW_PIXEL_SIZE_CHANGED => {
+                    let e = &event.window;
+                    if e.data1 > 0 && e.data2 > 0 {
+                        let width = e.data1 as u32;
+                        let height = e.data2 as u32;
+                        return Some(Event::WindowPixelSizeChanged {
+                            window: WindowID::from_raw(e.windowID),
+                            width,
+                            height,
+                        });
+                    }
+                }
+
+                SDL_EVENT_WINDOW_DISPLAY_SCALE_CHANGED => {
+                    let window = SDL_GetWindowFromID(event.window.windowID);
+
+                    if !window.is_null() {
+                        return Some(Event::WindowDisplayScaleChanged {
+                            window: WindowID::from_raw(event.window.windowID),
+                            scale: SDL_GetWindowDisplayScale(window),
+                        });
                     }
                 }
 
+                SDL_EVENT_WINDOW_MOVED => {
+                    return Some(Event::WindowMoved {
+                        window: WindowID::from_raw(event.window.windowID),
+                        x: event.window.data1,
+                        y: event.window.data2,
+                    });
+                }
+
+                SDL_EVENT_WINDOW_FOCUS_GAINED => {
+                    return Some(Event::WindowFocusGained {
+                        window: WindowID::from_raw(event.window.windowID),
+                    });
+                }
+
+                SDL_EVENT_WINDOW_FOCUS_LOST => {
+                    return Some(Event::WindowFocusLost {
+                        window: WindowID::from_raw(event.window.windowID),
+                    });
+                }
+
+                SDL_EVENT_WINDOW_MINIMIZED => {
+                    return Some(Event::WindowMinimized {
+                        window: WindowID::from_raw(event.window.windowID),
+                    });
+                }
+
+                SDL_EVENT_WINDOW_MAXIMIZED => {
+                    return Some(Event::WindowMaximized {
+                        window: WindowID::from_raw(event.window.windowID),
+                    });
+                }
+
+                SDL_EVENT_WINDOW_RESTORED => {
+                    return Some(Event::WindowRestored {
+                        window: WindowID::from_raw(event.window.windowID),
+                    });
+                }
+
+                SDL_EVENT_WINDOW_CLOSE_REQUESTED => {
+                    return Some(Event::WindowCloseRequested {
+                        window: WindowID::from_raw(event.window.windowID),
+                    });
+                }
+
+                SDL_EVENT_DROP_FILE if !event.drop.data.is_null() => {
+                    let path = std::ffi::CStr::from_ptr(event.drop.data)
+                        .to_string_lossy()
+                        .into_owned();
+
+                    return Some(Event::FileDropped {
+                        window: WindowID::from_raw(event.drop.windowID),
+                        path: path.into(),
+                    });
+                }
+
+                SDL_EVENT_DROP_TEXT if !event.drop.data.is_null() => {
+                    let text = std::ffi::CStr::from_ptr(event.drop.data)
+                        .to_string_lossy()
+                        .into_owned();
+
+                    return Some(Event::TextDropped {
+                        window: WindowID::from_raw(event.drop.windowID),
+                        text,
+                    });
+                }
+
                 SDL_EVENT_TEXT_INPUT => {
                     let text = std::ffi::CStr::from_ptr(event.text.text)
                         .to_string_lossy()
                         .into_owned();
 
-                    return Some(Event::TextInput { text });
+                    return Some(Event::TextInput {
+                        window: WindowID::from_raw(event.text.windowID),
+                        text,
+                    });
+                }
+
+                SDL_EVENT_CLIPBOARD_UPDATE => {
+                    return Some(Event::ClipboardUpdated);
+                }
+
+                SDL_EVENT_DISPLAY_ADDED => {
+                    return Some(Event::DisplayAdded {
+                        display: Display::from_raw(event.display.displayID),
+                    });
+                }
+
+                SDL_EVENT_DISPLAY_REMOVED => {
+                    return Some(Event::DisplayRemoved {
+                        display: Display::from_raw(event.display.displayID),
+                    });
+                }
+
+                SDL_EVENT_DISPLAY_ORIENTATION
+                | SDL_EVENT_DISPLAY_MOVED
+                | SDL_EVENT_DISPLAY_DESKTOP_MODE_CHANGED
+                | SDL_EVENT_DISPLAY_CURRENT_MODE_CHANGED
+                | SDL_EVENT_DISPLAY_CONTENT_SCALE_CHANGED => {
+                    return Some(Event::DisplayChanged {
+                        display: Display::from_raw(event.display.displayID),
+                    });
                 }
 
                 _ => {}
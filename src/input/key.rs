@@ -26,6 +26,8 @@ keys! {
     CapsLock => SDL_SCANCODE_CAPSLOCK,
     Escape => SDL_SCANCODE_ESCAPE,
     Delete => SDL_SCANCODE_DELETE,
+    Home => SDL_SCANCODE_HOME,
+    End => SDL_SCANCODE_END,
 
     LeftShift => SDL_SCANCODE_LSHIFT,
     RightShift => SDL_SCANCODE_RSHIFT,
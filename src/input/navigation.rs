@@ -0,0 +1,226 @@
+use glam::Vec2;
+
+use crate::graphics::Rectangle;
+
+use super::{GamepadButton, GamepadStick, Input, Key};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn to_vec2(&self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::NEG_Y,
+            Direction::Down => Vec2::Y,
+            Direction::Left => Vec2::NEG_X,
+            Direction::Right => Vec2::X,
+        }
+    }
+
+    fn from_vec2(v: Vec2) -> Direction {
+        if v.x.abs() > v.y.abs() {
+            if v.x < 0.0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            }
+        } else if v.y < 0.0 {
+            Direction::Up
+        } else {
+            Direction::Down
+        }
+    }
+}
+
+/// Finds the index of the nearest rectangle in the given direction.
+///
+/// Candidates that are off to the side are penalized more heavily than ones that are
+/// further away, so that navigation tends to stay in the same row or column.
+pub fn find_nearest(
+    from: Rectangle,
+    candidates: &[Rectangle],
+    direction: Direction,
+) -> Option<usize> {
+    let origin = from.center();
+    let dir = direction.to_vec2();
+    let perp = dir.perp();
+
+    candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, rect)| {
+            let offset = rect.center() - origin;
+            let along = offset.dot(dir);
+
+            if along <= 0.0 {
+                return None;
+            }
+
+            Some((i, along + offset.dot(perp).abs() * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+}
+
+/// Tracks which of a set of UI elements is focused, and moves the focus based on the
+/// keyboard, D-pad, stick and mouse.
+///
+/// The stick uses separate press and release thresholds, so that it does not flicker
+/// between directions when held near the edge of the deadzone.
+pub struct FocusNavigator {
+    focused: Option<usize>,
+
+    press_threshold: f32,
+    release_threshold: f32,
+    repeat_delay: u32,
+    repeat_interval: u32,
+
+    held: Option<Direction>,
+    repeat_timer: u32,
+    last_mouse_position: Vec2,
+}
+
+impl FocusNavigator {
+    pub fn new() -> FocusNavigator {
+        FocusNavigator {
+            focused: None,
+
+            press_threshold: 0.6,
+            release_threshold: 0.3,
+            repeat_delay: 30,
+            repeat_interval: 8,
+
+            held: None,
+            repeat_timer: 0,
+            last_mouse_position: Vec2::ZERO,
+        }
+    }
+
+    /// Sets how far the stick needs to be pushed to start moving, and how far it needs to
+    /// return before it is considered released.
+    pub fn thresholds(mut self, press: f32, release: f32) -> Self {
+        self.press_threshold = press;
+        self.release_threshold = f32::min(release, press);
+        self
+    }
+
+    /// Sets how many ticks a direction must be held before it starts repeating, and how
+    /// many ticks there are between each repeat.
+    pub fn repeat(mut self, delay: u32, interval: u32) -> Self {
+        self.repeat_delay = delay;
+        self.repeat_interval = interval.max(1);
+        self
+    }
+
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: Option<usize>) {
+        self.focused = focused;
+    }
+
+    /// Moves the focus between `items` based on the current input state, returning
+    /// the new focus if it changed.
+    ///
+    /// This should be called once per `update`.
+    pub fn update(&mut self, input: &Input, player: usize, items: &[Rectangle]) -> Option<usize> {
+        if self.focused.is_some_and(|i| i >= items.len()) {
+            self.focused = None;
+        }
+
+        let mouse_position = input.mouse_position();
+
+        if mouse_position != self.last_mouse_position {
+            self.last_mouse_position = mouse_position;
+
+            if let Some(i) = items.iter().position(|r| r.contains_point(mouse_position)) {
+                return self.change_focus(Some(i));
+            }
+        }
+
+        let direction = self.poll_direction(input, player)?;
+
+        let next = match self.focused {
+            Some(current) => find_nearest(items[current], items, direction),
+            None if !items.is_empty() => Some(0),
+            None => None,
+        };
+
+        match next {
+            Some(i) => self.change_focus(Some(i)),
+            None => None,
+        }
+    }
+
+    fn change_focus(&mut self, focused: Option<usize>) -> Option<usize> {
+        if self.focused != focused {
+            self.focused = focused;
+            focused
+        } else {
+            None
+        }
+    }
+
+    fn poll_direction(&mut self, input: &Input, player: usize) -> Option<Direction> {
+        const BUTTONS: [(Key, GamepadButton, Direction); 4] = [
+            (Key::Up, GamepadButton::Up, Direction::Up),
+            (Key::Down, GamepadButton::Down, Direction::Down),
+            (Key::Left, GamepadButton::Left, Direction::Left),
+            (Key::Right, GamepadButton::Right, Direction::Right),
+        ];
+
+        for (key, button, direction) in BUTTONS {
+            if input.is_key_pressed(key) || input.is_gamepad_button_pressed(player, button) {
+                self.held = Some(direction);
+                self.repeat_timer = self.repeat_delay;
+                return Some(direction);
+            }
+        }
+
+        let held_button = BUTTONS.iter().find(|(key, button, _)| {
+            input.is_key_down(*key) || input.is_gamepad_button_down(player, *button)
+        });
+
+        let stick = input.gamepad_stick(player, GamepadStick::LeftStick);
+
+        let current = if let Some((_, _, direction)) = held_button {
+            Some(*direction)
+        } else if stick.length() >= self.press_threshold {
+            Some(Direction::from_vec2(stick))
+        } else if stick.length() >= self.release_threshold {
+            self.held
+        } else {
+            None
+        };
+
+        match current {
+            Some(direction) if self.held != Some(direction) => {
+                self.held = Some(direction);
+                self.repeat_timer = self.repeat_delay;
+                Some(direction)
+            }
+
+            Some(direction) => {
+                self.repeat_timer = self.repeat_timer.saturating_sub(1);
+
+                if self.repeat_timer == 0 {
+                    self.repeat_timer = self.repeat_interval;
+                    Some(direction)
+                } else {
+                    None
+                }
+            }
+
+            None => {
+                self.held = None;
+                None
+            }
+        }
+    }
+}
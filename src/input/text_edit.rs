@@ -0,0 +1,433 @@
+use std::ops::Range;
+
+use crate::graphics::SpriteFont;
+use crate::window::Window;
+
+use super::{Event, Input, Key};
+
+const MAX_UNDO_STEPS: usize = 100;
+
+#[derive(Clone)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+    anchor: Option<usize>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+}
+
+/// The state of an editable, single-line text field.
+///
+/// The cursor and selection are stored as byte offsets into the UTF-8 buffer, and
+/// will always lie on a character boundary.
+///
+/// Remember to call `Window::start_text_input` while a field is focused, otherwise
+/// no `TextInput` events will be received.
+pub struct TextEditState {
+    text: String,
+    cursor: usize,
+    anchor: Option<usize>,
+    max_length: Option<usize>,
+
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    last_edit: Option<EditKind>,
+}
+
+impl TextEditState {
+    pub fn new() -> TextEditState {
+        TextEditState::with_text("")
+    }
+
+    pub fn with_text(text: &str) -> TextEditState {
+        TextEditState {
+            text: text.to_owned(),
+            cursor: text.len(),
+            anchor: None,
+            max_length: None,
+
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: None,
+        }
+    }
+
+    /// Sets the maximum number of characters that the field can hold.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the contents of the field, clearing the undo history.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_owned();
+        self.cursor = self.text.len();
+        self.anchor = None;
+
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_edit = None;
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the byte range of the current selection, if there is one.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        match self.anchor {
+            Some(anchor) if anchor != self.cursor => {
+                Some(usize::min(anchor, self.cursor)..usize::max(anchor, self.cursor))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|range| &self.text[range])
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.text.len();
+        self.last_edit = None;
+    }
+
+    /// Handles a keyboard or text input event, returning whether the text was changed.
+    ///
+    /// `input` is used to check which modifier keys are held, so this should be called
+    /// from `EventHandler::event`, after the app's input state has been updated. The
+    /// window is used to access the clipboard, and events from other windows are ignored.
+    pub fn event(&mut self, window: &mut Window, input: &Input, event: &Event) -> bool {
+        if event.window() != Some(window.id()) {
+            return false;
+        }
+
+        match event {
+            Event::TextInput { text, .. } => self.insert(text),
+            Event::KeyDown { key, .. } | Event::KeyRepeat { key, .. } => {
+                self.key(window, input, *key)
+            }
+            _ => false,
+        }
+    }
+
+    fn key(&mut self, window: &mut Window, input: &Input, key: Key) -> bool {
+        let shift = input.is_key_down(Key::LeftShift) || input.is_key_down(Key::RightShift);
+        let ctrl = input.is_key_down(Key::LeftCtrl)
+            || input.is_key_down(Key::RightCtrl)
+            || input.is_key_down(Key::LeftCommand)
+            || input.is_key_down(Key::RightCommand);
+
+        match key {
+            Key::Left if ctrl => self.move_to(self.prev_word_boundary(), shift),
+            Key::Right if ctrl => self.move_to(self.next_word_boundary(), shift),
+            Key::Left => match self.selection() {
+                Some(range) if !shift => self.move_to(range.start, false),
+                _ => self.move_to(self.prev_char_boundary(self.cursor), shift),
+            },
+            Key::Right => match self.selection() {
+                Some(range) if !shift => self.move_to(range.end, false),
+                _ => self.move_to(self.next_char_boundary(self.cursor), shift),
+            },
+            Key::Home => self.move_to(0, shift),
+            Key::End => self.move_to(self.text.len(), shift),
+
+            Key::Backspace if ctrl && self.selection().is_none() => {
+                self.anchor = Some(self.prev_word_boundary());
+                return self.delete_selection();
+            }
+            Key::Delete if ctrl && self.selection().is_none() => {
+                self.anchor = Some(self.next_word_boundary());
+                return self.delete_selection();
+            }
+            Key::Backspace => return self.backspace(),
+            Key::Delete => return self.delete(),
+
+            Key::A if ctrl => self.select_all(),
+            Key::C if ctrl => {
+                if let Some(text) = self.copy() {
+                    window.set_clipboard_text(&text);
+                }
+            }
+            Key::X if ctrl => {
+                // If the clipboard can't be set, the text is left in place rather
+                // than being lost.
+                if let Some(text) = self.copy() {
+                    if window.set_clipboard_text(&text) {
+                        return self.delete_selection();
+                    }
+                }
+            }
+            Key::V if ctrl => {
+                if let Some(text) = window.clipboard_text() {
+                    return self.paste(&text);
+                }
+            }
+            Key::Z if ctrl && shift => return self.redo(),
+            Key::Z if ctrl => return self.undo(),
+            Key::Y if ctrl => return self.redo(),
+
+            _ => {}
+        }
+
+        false
+    }
+
+    /// Inserts text at the cursor, replacing the selection if there is one.
+    ///
+    /// Control characters are stripped, and the text will be truncated if it would
+    /// exceed the maximum length.
+    pub fn insert(&mut self, text: &str) -> bool {
+        let selection = self.selection();
+        let selected_chars = selection
+            .as_ref()
+            .map(|range| self.text[range.clone()].chars().count())
+            .unwrap_or(0);
+
+        let available = match self.max_length {
+            Some(max) => max.saturating_sub(self.text.chars().count() - selected_chars),
+            None => usize::MAX,
+        };
+
+        let filtered: String = text
+            .chars()
+            .filter(|ch| !ch.is_control())
+            .take(available)
+            .collect();
+
+        if filtered.is_empty() && selection.is_none() {
+            return false;
+        }
+
+        self.push_undo(EditKind::Insert);
+
+        let range = selection.unwrap_or(self.cursor..self.cursor);
+        self.text.replace_range(range.clone(), &filtered);
+        self.cursor = range.start + filtered.len();
+        self.anchor = None;
+
+        true
+    }
+
+    /// Deletes the selection, or the character before the cursor.
+    pub fn backspace(&mut self) -> bool {
+        if self.selection().is_none() {
+            self.anchor = Some(self.prev_char_boundary(self.cursor));
+        }
+
+        self.delete_selection()
+    }
+
+    /// Deletes the selection, or the character after the cursor.
+    pub fn delete(&mut self) -> bool {
+        if self.selection().is_none() {
+            self.anchor = Some(self.next_char_boundary(self.cursor));
+        }
+
+        self.delete_selection()
+    }
+
+    /// Returns the selected text, so that it can be placed on the clipboard.
+    pub fn copy(&self) -> Option<String> {
+        self.selected_text().map(str::to_owned)
+    }
+
+    /// Removes the selected text and returns it, so that it can be placed on the clipboard.
+    pub fn cut(&mut self) -> Option<String> {
+        let text = self.copy()?;
+        self.delete_selection();
+        Some(text)
+    }
+
+    /// Inserts text from the clipboard. Newlines are replaced with spaces.
+    pub fn paste(&mut self, text: &str) -> bool {
+        self.last_edit = None;
+        let changed = self.insert(&text.replace(['\r', '\n'], " "));
+        self.last_edit = None;
+        changed
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(snapshot) => {
+                let current = self.restore(snapshot);
+                self.redo_stack.push(current);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(snapshot) => {
+                let current = self.restore(snapshot);
+                self.undo_stack.push(current);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the horizontal offset of the cursor, relative to the start of the text.
+    pub fn caret_offset(&self, font: &SpriteFont) -> f32 {
+        font.measure(&self.text[..self.cursor]).x
+    }
+
+    /// Returns the horizontal start and end offsets of the selection, relative to the
+    /// start of the text.
+    pub fn selection_offsets(&self, font: &SpriteFont) -> Option<(f32, f32)> {
+        self.selection().map(|range| {
+            (
+                font.measure(&self.text[..range.start]).x,
+                font.measure(&self.text[..range.end]).x,
+            )
+        })
+    }
+
+    /// Finds the character boundary closest to the given horizontal offset. This is
+    /// useful for positioning the cursor based on a mouse click.
+    pub fn index_at_offset(&self, font: &SpriteFont, offset: f32) -> usize {
+        let mut prev_index = 0;
+        let mut prev_x = 0.0;
+
+        for (index, _) in self.text.char_indices().skip(1) {
+            let x = font.measure(&self.text[..index]).x;
+
+            if x >= offset {
+                return if offset - prev_x < x - offset {
+                    prev_index
+                } else {
+                    index
+                };
+            }
+
+            prev_index = index;
+            prev_x = x;
+        }
+
+        if offset - prev_x < font.measure(&self.text).x - offset {
+            prev_index
+        } else {
+            self.text.len()
+        }
+    }
+
+    /// Moves the cursor to the given byte offset. If `select` is true, the selection
+    /// is extended to the new position.
+    pub fn set_cursor(&mut self, index: usize, select: bool) {
+        let mut index = index.min(self.text.len());
+
+        while !self.text.is_char_boundary(index) {
+            index -= 1;
+        }
+
+        self.move_to(index, select);
+    }
+
+    fn move_to(&mut self, index: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+
+        self.cursor = index;
+        self.last_edit = None;
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some(range) => {
+                self.push_undo(EditKind::Delete);
+
+                self.text.replace_range(range.clone(), "");
+                self.cursor = range.start;
+                self.anchor = None;
+
+                true
+            }
+            None => {
+                self.anchor = None;
+                false
+            }
+        }
+    }
+
+    fn push_undo(&mut self, kind: EditKind) {
+        self.redo_stack.clear();
+
+        // Consecutive edits of the same kind get merged into one undo step.
+        if self.last_edit != Some(kind) {
+            self.undo_stack.push(Snapshot {
+                text: self.text.clone(),
+                cursor: self.cursor,
+                anchor: self.anchor,
+            });
+
+            if self.undo_stack.len() > MAX_UNDO_STEPS {
+                self.undo_stack.remove(0);
+            }
+        }
+
+        self.last_edit = Some(kind);
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        self.last_edit = None;
+
+        Snapshot {
+            text: std::mem::replace(&mut self.text, snapshot.text),
+            cursor: std::mem::replace(&mut self.cursor, snapshot.cursor),
+            anchor: std::mem::replace(&mut self.anchor, snapshot.anchor),
+        }
+    }
+
+    fn prev_char_boundary(&self, index: usize) -> usize {
+        self.text[..index]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    fn next_char_boundary(&self, index: usize) -> usize {
+        self.text[index..]
+            .chars()
+            .next()
+            .map(|ch| index + ch.len_utf8())
+            .unwrap_or(index)
+    }
+
+    fn prev_word_boundary(&self) -> usize {
+        let before = &self.text[..self.cursor];
+        let trimmed = before.trim_end_matches(char::is_whitespace);
+
+        trimmed
+            .char_indices()
+            .rev()
+            .find(|(_, ch)| ch.is_whitespace())
+            .map(|(i, ch)| i + ch.len_utf8())
+            .unwrap_or(0)
+    }
+
+    fn next_word_boundary(&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let skipped = after.len() - after.trim_start_matches(char::is_whitespace).len();
+
+        after[skipped..]
+            .char_indices()
+            .find(|(_, ch)| ch.is_whitespace())
+            .map(|(i, _)| self.cursor + skipped + i)
+            .unwrap_or(self.text.len())
+    }
+}
@@ -0,0 +1,109 @@
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use sdl3_sys::events::*;
+
+use crate::window::register_event_type;
+
+/// Sends messages of type `T` into the app's event loop, where they will be received as
+/// an `Event::User`.
+///
+/// Senders can be cloned and moved to other threads, so this is a good way for worker
+/// threads to report back to the main thread.
+pub struct EventSender<T> {
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T> EventSender<T>
+where
+    T: Send + 'static,
+{
+    pub fn new() -> EventSender<T> {
+        // Senders can be moved to other threads, so this needs to happen first.
+        user_event_type();
+
+        EventSender {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Pushes a message onto the event queue. Returns false if the event could not be
+    /// queued, e.g. because the queue is full or the app has shut down.
+    pub fn send(&self, message: T) -> bool {
+        let payload: Box<Box<dyn Any + Send>> = Box::new(Box::new(message));
+
+        unsafe {
+            let mut event: SDL_Event = std::mem::zeroed();
+
+            event.user.r#type = user_event_type();
+            event.user.data1 = Box::into_raw(payload) as *mut _;
+
+            if !SDL_PushEvent(&mut event) {
+                drop(Box::from_raw(event.user.data1 as *mut Box<dyn Any + Send>));
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        EventSender {
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A message that was sent via an `EventSender`.
+///
+/// Use `is` or `downcast_ref` to check what type of message it is.
+#[derive(Clone)]
+pub struct UserEvent(Rc<dyn Any>);
+
+impl UserEvent {
+    pub fn is<T: 'static>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.0.downcast_ref::<T>()
+    }
+}
+
+impl PartialEq for UserEvent {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for UserEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UserEvent(...)")
+    }
+}
+
+/// Returns the SDL event type used to deliver user events.
+pub(crate) fn user_event_type() -> u32 {
+    static EVENT_TYPE: OnceLock<u32> = OnceLock::new();
+
+    register_event_type(&EVENT_TYPE)
+}
+
+/// Takes ownership of the message attached to a user event.
+///
+/// # Safety
+///
+/// The event must have been pushed by `EventSender::send`, and must only be read once.
+pub(crate) unsafe fn take_user_event(event: &SDL_UserEvent) -> UserEvent {
+    unsafe {
+        let message = Box::from_raw(event.data1 as *mut Box<dyn Any + Send>);
+        let message: Box<dyn Any> = *message;
+
+        UserEvent(Rc::from(message))
+    }
+}
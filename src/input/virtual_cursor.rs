@@ -0,0 +1,138 @@
+use glam::Vec2;
+
+use crate::graphics::Rectangle;
+
+use super::{GamepadStick, Input};
+
+/// A pointer that can be driven by either the mouse or a gamepad stick.
+///
+/// Stick movement accelerates the longer it is held, and when the stick is released
+/// near a snap target, the cursor will be pulled towards the center of it.
+pub struct VirtualCursor {
+    position: Vec2,
+    bounds: Rectangle,
+
+    base_speed: f32,
+    max_speed: f32,
+    acceleration: f32,
+    snap_radius: f32,
+    snap_strength: f32,
+
+    current_speed: f32,
+    last_mouse_position: Vec2,
+}
+
+impl VirtualCursor {
+    /// Creates a new cursor, positioned at the center of the given bounds.
+    pub fn new(bounds: Rectangle) -> VirtualCursor {
+        VirtualCursor {
+            position: bounds.center(),
+            bounds,
+
+            base_speed: 4.0,
+            max_speed: 12.0,
+            acceleration: 0.25,
+            snap_radius: 24.0,
+            snap_strength: 0.35,
+
+            current_speed: 4.0,
+            last_mouse_position: Vec2::ZERO,
+        }
+    }
+
+    /// Sets the speed (in pixels per tick) that the cursor moves at when the stick is
+    /// first pushed, and the speed that it accelerates up to.
+    pub fn speed(mut self, base: f32, max: f32) -> Self {
+        self.base_speed = base;
+        self.max_speed = max;
+        self.current_speed = base;
+        self
+    }
+
+    /// Sets how much the speed increases by for each tick that the stick is held.
+    pub fn acceleration(mut self, acceleration: f32) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    /// Sets how close to a snap target the cursor needs to be to get pulled towards it,
+    /// and what fraction of the remaining distance is covered each tick.
+    pub fn snapping(mut self, radius: f32, strength: f32) -> Self {
+        self.snap_radius = radius;
+        self.snap_strength = strength.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = self.clamp_to_bounds(position);
+    }
+
+    pub fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+        self.position = self.clamp_to_bounds(self.position);
+    }
+
+    /// Updates the cursor position based on the current input state.
+    ///
+    /// If the mouse has moved since the last update, the cursor will jump to the
+    /// mouse position. Otherwise, the given stick will move the cursor, snapping to
+    /// the nearest of `snap_targets` when the stick is at rest.
+    ///
+    /// This should be called once per `update`.
+    pub fn update(
+        &mut self,
+        input: &Input,
+        player: usize,
+        stick: GamepadStick,
+        snap_targets: &[Rectangle],
+    ) {
+        let mouse_position = input.mouse_position();
+
+        if mouse_position != self.last_mouse_position {
+            self.last_mouse_position = mouse_position;
+            self.current_speed = self.base_speed;
+            self.set_position(mouse_position);
+            return;
+        }
+
+        let direction = input.gamepad_stick(player, stick).clamp_length_max(1.0);
+
+        if direction != Vec2::ZERO {
+            self.set_position(self.position + direction * self.current_speed);
+            self.current_speed = f32::min(self.current_speed + self.acceleration, self.max_speed);
+        } else {
+            self.current_speed = self.base_speed;
+
+            if let Some(target) = self.nearest_snap_target(snap_targets) {
+                let new_position = self.position.lerp(target, self.snap_strength);
+
+                self.set_position(if new_position.distance(target) < 0.5 {
+                    target
+                } else {
+                    new_position
+                });
+            }
+        }
+    }
+
+    fn nearest_snap_target(&self, snap_targets: &[Rectangle]) -> Option<Vec2> {
+        snap_targets
+            .iter()
+            .map(|rect| (rect.center(), rect.center().distance(self.position)))
+            .filter(|(_, distance)| *distance <= self.snap_radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(center, _)| center)
+    }
+
+    fn clamp_to_bounds(&self, position: Vec2) -> Vec2 {
+        position.clamp(self.bounds.top_left(), self.bounds.bottom_right())
+    }
+}
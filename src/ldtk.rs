@@ -1,5 +1,3 @@
-use std::path::Path;
-
 use glam::{BVec2, IVec2};
 use serde::{Deserialize, Deserializer};
 
@@ -11,9 +9,17 @@ pub struct Project {
 }
 
 impl Project {
-    pub fn from_file(path: impl AsRef<Path>) -> Project {
-        let json = std::fs::read_to_string(path).unwrap();
-        serde_json::from_str(&json).unwrap()
+    pub fn from_file(path: &str) -> Project {
+        Project::try_from_file(path).unwrap()
+    }
+
+    /// Like `from_file`, but returns an error instead of panicking if the file can't be
+    /// read or parsed.
+    pub fn try_from_file(path: &str) -> Result<Project, String> {
+        let json = crate::fs::try_read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path, e))?;
+
+        serde_json::from_str(&json).map_err(|e| format!("failed to parse {}: {}", path, e))
     }
 
     pub fn get_level(&self, id: &str) -> Option<&Level> {
@@ -3,10 +3,14 @@
 
 // ===== Core =====
 pub mod app;
+pub mod assets;
+pub mod coroutine;
 pub mod fs;
 pub mod graphics;
 pub mod input;
+pub mod scene;
 pub mod time;
+pub mod tween;
 pub mod window;
 
 pub use glam as math;
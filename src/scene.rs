@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use crate::app::{App, EventHandler, LoopMode};
+use crate::graphics::{Batcher, Transition, TransitionKind};
+use crate::input::Event;
+
+/// A screen of the game, such as a title screen, a level, or a pause menu.
+///
+/// Scenes live on a `SceneStack`. Only the top scene receives events and updates,
+/// and scenes can request changes to the stack via the `SceneCommands` they are given.
+pub trait Scene {
+    /// Runs when the scene is added to the stack.
+    fn enter(&mut self, _app: &mut App) {}
+
+    /// Runs when the scene is removed from the stack.
+    fn exit(&mut self, _app: &mut App) {}
+
+    /// Runs when another scene is pushed on top of this one.
+    fn pause(&mut self, _app: &mut App) {}
+
+    /// Runs when this scene becomes the top of the stack again.
+    fn resume(&mut self, _app: &mut App) {}
+
+    fn event(&mut self, _app: &mut App, _scenes: &mut SceneCommands, _event: Event) {}
+    fn update(&mut self, _app: &mut App, _scenes: &mut SceneCommands) {}
+    fn late_update(&mut self, _app: &mut App, _scenes: &mut SceneCommands) {}
+    fn frame_update(&mut self, _app: &mut App, _scenes: &mut SceneCommands, _dt: Duration) {}
+    fn draw(&mut self, _app: &mut App) {}
+
+    /// Runs when the main window's size in pixels changes. Unlike the other callbacks,
+    /// this is called for every scene on the stack, not just the top one.
+    fn resize(&mut self, _app: &mut App, _width: u32, _height: u32) {}
+
+    /// Returns whether the scene below this one should be drawn first. This is useful
+    /// for overlays, like pause menus.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+enum Command {
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+}
+
+/// Changes to the scene stack that have been requested by a scene.
+///
+/// These are applied in order once the current callback returns, or on the next draw if
+/// a transition was requested.
+pub struct SceneCommands {
+    commands: Vec<Command>,
+    transition: Option<(TransitionKind, u32)>,
+}
+
+impl SceneCommands {
+    /// Adds a scene to the top of the stack, pausing the current one.
+    pub fn push(&mut self, scene: impl Scene + 'static) {
+        self.commands.push(Command::Push(Box::new(scene)));
+    }
+
+    /// Removes the top scene from the stack, resuming the one below it.
+    pub fn pop(&mut self) {
+        self.commands.push(Command::Pop);
+    }
+
+    /// Replaces the top scene on the stack.
+    pub fn replace(&mut self, scene: impl Scene + 'static) {
+        self.commands.push(Command::Replace(Box::new(scene)));
+    }
+
+    /// Animates the changes requested in the current callback with a transition,
+    /// lasting the given number of ticks.
+    ///
+    /// The changes are delayed until the next draw, so that the outgoing scenes can be
+    /// captured. If no changes are requested in the same callback, this does nothing.
+    pub fn transition(&mut self, kind: TransitionKind, duration: u32) {
+        self.transition = Some((kind, duration));
+    }
+}
+
+/// An `EventHandler` that manages a stack of scenes.
+///
+/// The app will stop running once the last scene has been popped.
+///
+/// When a transition is requested, the changes are held back until the next draw. The
+/// outgoing scenes are drawn and captured from the window, then the changes are applied,
+/// and the incoming frame is drawn over the captured one until the transition finishes.
+/// Transitions are advanced once per `update`, or by the frame time in
+/// `LoopMode::Variable`.
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    commands: SceneCommands,
+
+    pending_transition: Option<(TransitionKind, u32)>,
+    deferred: Vec<Command>,
+    transition: Option<Transition>,
+    batch: Option<Batcher>,
+}
+
+impl SceneStack {
+    /// Creates a scene stack with an initial scene. The scene will be entered when
+    /// the app starts running.
+    pub fn new(initial: impl Scene + 'static) -> SceneStack {
+        SceneStack {
+            scenes: Vec::new(),
+            commands: SceneCommands {
+                commands: vec![Command::Push(Box::new(initial))],
+                transition: None,
+            },
+
+            pending_transition: None,
+            deferred: Vec::new(),
+            transition: None,
+            batch: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Adds a scene to the top of the stack, pausing the current one.
+    pub fn push(&mut self, app: &mut App, scene: impl Scene + 'static) {
+        self.commands.push(scene);
+        self.apply_commands(app);
+    }
+
+    /// Removes the top scene from the stack, resuming the one below it.
+    pub fn pop(&mut self, app: &mut App) {
+        self.commands.pop();
+        self.apply_commands(app);
+    }
+
+    /// Replaces the top scene on the stack.
+    pub fn replace(&mut self, app: &mut App, scene: impl Scene + 'static) {
+        self.commands.replace(scene);
+        self.apply_commands(app);
+    }
+
+    /// Returns whether a transition is currently playing, or waiting to start on the
+    /// next draw.
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some() || self.pending_transition.is_some()
+    }
+
+    fn apply_commands(&mut self, app: &mut App) {
+        let transition = self.commands.transition.take();
+        let commands = std::mem::take(&mut self.commands.commands);
+
+        if commands.is_empty() {
+            return;
+        }
+
+        if transition.is_some() {
+            self.pending_transition = transition;
+        }
+
+        if self.pending_transition.is_some() {
+            self.deferred.extend(commands);
+        } else {
+            self.run_commands(app, commands);
+        }
+    }
+
+    fn run_commands(&mut self, app: &mut App, commands: Vec<Command>) {
+        for command in commands {
+            match command {
+                Command::Push(mut scene) => {
+                    if let Some(top) = self.scenes.last_mut() {
+                        top.pause(app);
+                    }
+
+                    scene.enter(app);
+                    self.scenes.push(scene);
+                }
+
+                Command::Pop => {
+                    if let Some(mut scene) = self.scenes.pop() {
+                        scene.exit(app);
+                    }
+
+                    if let Some(top) = self.scenes.last_mut() {
+                        top.resume(app);
+                    }
+                }
+
+                Command::Replace(mut scene) => {
+                    if let Some(mut old) = self.scenes.pop() {
+                        old.exit(app);
+                    }
+
+                    scene.enter(app);
+                    self.scenes.push(scene);
+                }
+            }
+        }
+
+        if self.scenes.is_empty() {
+            app.is_running = false;
+        }
+    }
+
+    fn start_transition(&mut self, app: &mut App, kind: TransitionKind, duration: u32) {
+        self.draw_scenes(app);
+
+        let (width, height) = app.window.size();
+        let transition = Transition::new(&app.gfx, kind, duration, width as i32, height as i32);
+
+        transition.from_canvas().copy_from_window(&app.window);
+
+        self.transition = Some(transition);
+
+        let commands = std::mem::take(&mut self.deferred);
+        self.run_commands(app, commands);
+    }
+
+    fn draw_scenes(&mut self, app: &mut App) {
+        // Find the lowest scene that is visible through the overlays above it.
+        let mut first = self.scenes.len().saturating_sub(1);
+
+        while first > 0 && self.scenes[first].is_overlay() {
+            first -= 1;
+        }
+
+        for scene in self.scenes.iter_mut().skip(first) {
+            scene.draw(app);
+        }
+    }
+}
+
+impl EventHandler for SceneStack {
+    fn init(&mut self, app: &mut App) {
+        self.apply_commands(app);
+    }
+
+    fn event(&mut self, app: &mut App, event: Event) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.event(app, &mut self.commands, event);
+            self.apply_commands(app);
+        }
+    }
+
+    fn update(&mut self, app: &mut App) {
+        if let Some(transition) = &mut self.transition {
+            transition.update();
+        }
+
+        if let Some(top) = self.scenes.last_mut() {
+            top.update(app, &mut self.commands);
+            self.apply_commands(app);
+        }
+    }
+
+    fn late_update(&mut self, app: &mut App) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.late_update(app, &mut self.commands);
+            self.apply_commands(app);
+        }
+    }
+
+    fn frame_update(&mut self, app: &mut App, dt: Duration) {
+        // `update` is never called in this mode, so convert the real frame time into
+        // ticks instead.
+        if app.loop_mode == LoopMode::Variable {
+            if let Some(transition) = &mut self.transition {
+                let timer = &app.timer;
+
+                transition.advance(
+                    timer.unscaled_frame_delta().as_secs_f32()
+                        / timer.unscaled_delta().as_secs_f32(),
+                );
+            }
+        }
+
+        if let Some(top) = self.scenes.last_mut() {
+            top.frame_update(app, &mut self.commands, dt);
+            self.apply_commands(app);
+        }
+    }
+
+    fn draw(&mut self, app: &mut App) {
+        if let Some((kind, duration)) = self.pending_transition.take() {
+            self.start_transition(app, kind, duration);
+        }
+
+        self.draw_scenes(app);
+
+        if let Some(transition) = &mut self.transition {
+            let batch = self.batch.get_or_insert_with(|| Batcher::new(&app.gfx));
+
+            transition.to_canvas().copy_from_window(&app.window);
+            transition.draw(batch, &app.window, app.timer.blend_factor());
+
+            if transition.is_finished() {
+                self.transition = None;
+            }
+        }
+    }
+
+    fn resize(&mut self, app: &mut App, width: u32, height: u32) {
+        for scene in &mut self.scenes {
+            scene.resize(app, width, height);
+        }
+    }
+
+    fn shutdown(&mut self, app: &mut App) {
+        while let Some(mut scene) = self.scenes.pop() {
+            scene.exit(app);
+        }
+    }
+}
@@ -1,82 +1,725 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// A source of time for a `Timer`.
+pub trait Clock {
+    /// Returns the time that has passed since some fixed point.
+    fn now(&self) -> Duration;
+
+    /// Blocks the thread for roughly the given duration.
+    fn sleep(&self, duration: Duration);
+
+    /// Busy-waits until `now` reaches the given time.
+    fn spin_until(&self, time: Duration);
+}
+
+/// A clock that uses the system's monotonic timer.
+pub struct SystemClock {
+    epoch: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            epoch: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+
+    fn spin_until(&self, time: Duration) {
+        while self.now() < time {
+            std::hint::spin_loop();
+        }
+    }
+}
+
+/// A clock that only moves when it is told to.
+///
+/// Clones share the same time, so a copy can be kept after passing one to a `Timer`
+/// (including one that has been moved to another thread). Sleeping and spinning advance
+/// the clock instantly, so a timer driven by this clock never blocks. This is useful for
+/// tests and headless simulations.
+#[derive(Clone)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock {
+            nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, time: Duration) {
+        self.nanos.store(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+
+    fn spin_until(&self, time: Duration) {
+        self.nanos
+            .fetch_max(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Statistics about how late the timer woke up compared to when the next update was due.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct JitterStats {
+    pub last: Duration,
+    pub average: Duration,
+    pub max: Duration,
+    pub samples: u32,
+}
+
 pub struct Timer {
-    start_time: Instant,
-    last_time: Instant,
+    clock: Box<dyn Clock + Send>,
+
+    start_time: Duration,
+    last_time: Duration,
     accumulated_time: Duration,
     target_time: Duration,
     max_lag: Duration,
+
+    frame_start: Duration,
+    frame_delta: Duration,
+
+    spin_threshold: Duration,
+    vsync_paced: bool,
+
+    time_scale: f64,
+    paused: bool,
+    freeze_ticks: u32,
+    tick_frozen: bool,
+    game_time: Duration,
+
+    jitter: JitterStats,
+    jitter_total: Duration,
 }
 
 impl Timer {
     pub fn new(tick_rate: f64) -> Timer {
+        Timer::with_clock(tick_rate, SystemClock::new())
+    }
+
+    pub fn with_clock(tick_rate: f64, clock: impl Clock + Send + 'static) -> Timer {
         let target_time = Duration::from_secs_f64(1.0 / tick_rate);
+        let now = clock.now();
 
         Timer {
-            start_time: Instant::now(),
-            last_time: Instant::now(),
+            clock: Box::new(clock),
+
+            start_time: now,
+            last_time: now,
             accumulated_time: Duration::ZERO,
             target_time,
             max_lag: target_time * 8,
+
+            frame_start: now,
+            frame_delta: Duration::ZERO,
+
+            spin_threshold: Duration::from_millis(2),
+            vsync_paced: false,
+
+            time_scale: 1.0,
+            paused: false,
+            freeze_ticks: 0,
+            tick_frozen: false,
+            game_time: Duration::ZERO,
+
+            jitter: JitterStats::default(),
+            jitter_total: Duration::ZERO,
         }
     }
 
     pub fn tick(&mut self) {
         self.advance_time();
         self.cap_accumulated_time();
+        self.end_frame_wait();
     }
 
+    /// Waits until enough time has accumulated for an update to be ready.
+    ///
+    /// The thread sleeps until the remaining time drops below the spin threshold, and then
+    /// spins for the rest, as sleeping is not precise enough on most platforms.
+    ///
+    /// If the timer is paced by vsync, this does not wait at all, as presenting the
+    /// frame will already have blocked until the display was ready.
     pub fn tick_until_update_ready(&mut self) {
         self.advance_time();
 
-        // TODO: This isn't accurate enough - need to sleep and then spin.
-        while self.accumulated_time < self.target_time {
-            std::thread::sleep(Duration::from_millis(1));
+        if !self.vsync_paced && self.accumulated_time < self.target_time {
+            loop {
+                let remaining = self.target_time.saturating_sub(self.accumulated_time);
+
+                if remaining.is_zero() {
+                    break;
+                }
+
+                if remaining > self.spin_threshold {
+                    self.clock.sleep(remaining - self.spin_threshold);
+                } else {
+                    self.clock.spin_until(self.last_time + remaining);
+                }
 
-            self.advance_time();
+                self.advance_time();
+            }
+
+            self.record_jitter(self.accumulated_time - self.target_time);
         }
 
         self.cap_accumulated_time();
+        self.end_frame_wait();
     }
 
     pub fn reset(&mut self) {
-        self.last_time = Instant::now();
+        self.last_time = self.clock.now();
         self.accumulated_time = Duration::ZERO;
+
+        self.frame_start = self.last_time;
+        self.frame_delta = Duration::ZERO;
     }
 
+    /// Consumes one tick's worth of time, returning whether an update should run.
+    ///
+    /// Updates always run at the fixed tick rate, even when the time is scaled, paused
+    /// or frozen - only the value returned by `delta` changes.
     pub fn consume_time(&mut self) -> bool {
         let ready = self.accumulated_time >= self.target_time;
 
         if ready {
             self.accumulated_time -= self.target_time;
+
+            self.tick_frozen = self.freeze_ticks > 0;
+            self.freeze_ticks = self.freeze_ticks.saturating_sub(1);
+
+            self.game_time += self.delta();
         }
 
         ready
     }
 
+    /// Consumes all of the accumulated time as one variable-length frame, advancing game
+    /// time by `frame_delta`. This is used instead of `consume_time` when there are no
+    /// fixed updates (e.g. in `LoopMode::Variable`), and leaves `blend_factor` at zero.
+    ///
+    /// When time is consumed this way, `freeze` counts frames rather than ticks.
+    pub fn consume_frame(&mut self) {
+        self.accumulated_time = Duration::ZERO;
+
+        self.tick_frozen = self.freeze_ticks > 0;
+        self.freeze_ticks = self.freeze_ticks.saturating_sub(1);
+
+        self.game_time += self.frame_delta();
+    }
+
+    /// Returns the amount of game time that passes in the current tick.
+    ///
+    /// This takes the time scale into account, and will be zero while paused or frozen.
     pub fn delta(&self) -> Duration {
+        if self.paused || self.tick_frozen {
+            Duration::ZERO
+        } else {
+            self.target_time.mul_f64(self.time_scale)
+        }
+    }
+
+    /// Returns the amount of real time that passes in each tick.
+    pub fn unscaled_delta(&self) -> Duration {
         self.target_time
     }
 
+    /// Returns the amount of game time that passed between the last two frames.
+    ///
+    /// This takes the time scale into account, and will be zero while paused or frozen.
+    /// It is capped to the maximum lag, so that long stalls don't cause huge jumps.
+    pub fn frame_delta(&self) -> Duration {
+        if self.paused || self.tick_frozen {
+            Duration::ZERO
+        } else {
+            self.frame_delta.mul_f64(self.time_scale)
+        }
+    }
+
+    /// Returns the amount of real time that passed between the last two frames.
+    pub fn unscaled_frame_delta(&self) -> Duration {
+        self.frame_delta
+    }
+
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Sets how fast game time passes, relative to real time. For example, 0.5 is half
+    /// speed, and 2.0 is double speed.
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses or unpauses game time.
+    ///
+    /// Updates will keep running so that menus can still be used, but `delta` will be
+    /// zero, so gameplay code should check `is_paused` and skip simulation.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Stops game time for the given number of ticks, starting from the next one. This
+    /// is useful for hitstop effects.
+    ///
+    /// Calling this while already frozen will extend the freeze if it is longer than
+    /// the time remaining.
+    pub fn freeze(&mut self, ticks: u32) {
+        self.freeze_ticks = self.freeze_ticks.max(ticks);
+    }
+
+    /// Returns whether game time is stopped for the current tick (or frame) due to `freeze`.
+    pub fn is_frozen(&self) -> bool {
+        self.tick_frozen
+    }
+
     pub fn blend_factor(&self) -> f32 {
         self.accumulated_time.as_secs_f32() / self.target_time.as_secs_f32()
     }
 
+    /// Sets the maximum amount of time that can build up between updates. Any time beyond
+    /// this will be dropped, to avoid the game trying to catch up after a long stall.
+    ///
+    /// When fast-forwarding with a `ManualClock`, this may need to be raised.
+    pub fn set_max_lag(&mut self, max_lag: Duration) {
+        self.max_lag = max_lag;
+    }
+
+    /// Sets how close to the next update the timer will stop sleeping and start spinning.
+    ///
+    /// Higher values are more accurate, but use more CPU. A threshold of zero will
+    /// never spin.
+    pub fn set_spin_threshold(&mut self, threshold: Duration) {
+        self.spin_threshold = threshold;
+    }
+
+    pub fn spin_threshold(&self) -> Duration {
+        self.spin_threshold
+    }
+
+    /// Sets whether frame pacing is left to vsync, rather than the timer waiting for the
+    /// next update.
+    ///
+    /// This should only be enabled if vsync is on - otherwise the game will run as fast
+    /// as possible, drawing multiple frames per update.
+    pub fn set_vsync_paced(&mut self, vsync_paced: bool) {
+        self.vsync_paced = vsync_paced;
+    }
+
+    pub fn is_vsync_paced(&self) -> bool {
+        self.vsync_paced
+    }
+
+    /// Returns statistics about how accurately the timer has been waking up.
+    pub fn jitter(&self) -> JitterStats {
+        self.jitter
+    }
+
+    pub fn reset_jitter(&mut self) {
+        self.jitter = JitterStats::default();
+        self.jitter_total = Duration::ZERO;
+    }
+
+    fn record_jitter(&mut self, jitter: Duration) {
+        self.jitter_total += jitter;
+
+        self.jitter.samples += 1;
+        self.jitter.last = jitter;
+        self.jitter.average = self.jitter_total / self.jitter.samples;
+        self.jitter.max = self.jitter.max.max(jitter);
+    }
+
     fn advance_time(&mut self) {
-        let current_time = Instant::now();
-        let time_advanced = current_time - self.last_time;
+        let current_time = self.clock.now();
+        let time_advanced = current_time.saturating_sub(self.last_time);
 
         self.accumulated_time += time_advanced;
         self.last_time = current_time;
     }
 
+    fn end_frame_wait(&mut self) {
+        self.frame_delta = self
+            .last_time
+            .saturating_sub(self.frame_start)
+            .min(self.max_lag);
+
+        self.frame_start = self.last_time;
+    }
+
     fn cap_accumulated_time(&mut self) {
         if self.accumulated_time > self.max_lag {
             self.accumulated_time = self.max_lag;
         }
     }
 
+    /// Returns the total amount of real time that has passed since the timer was created.
     pub fn total_time(&self) -> Duration {
-        self.start_time.elapsed()
+        self.clock.now().saturating_sub(self.start_time)
+    }
+
+    /// Returns the total amount of game time that has passed, taking the time scale,
+    /// pausing and freezing into account.
+    pub fn game_time(&self) -> Duration {
+        self.game_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+
+    const TICK_RATE: f64 = 60.0;
+
+    /// A manual clock that can be told to oversleep, like a real OS scheduler.
+    #[derive(Clone)]
+    struct SloppyClock {
+        manual: ManualClock,
+        oversleep: Arc<AtomicU64>,
+        spins: Arc<AtomicU32>,
+    }
+
+    impl SloppyClock {
+        fn new() -> SloppyClock {
+            SloppyClock {
+                manual: ManualClock::new(),
+                oversleep: Arc::new(AtomicU64::new(0)),
+                spins: Arc::new(AtomicU32::new(0)),
+            }
+        }
+
+        fn set_oversleep(&self, oversleep: Duration) {
+            self.oversleep
+                .store(oversleep.as_nanos() as u64, Ordering::Relaxed);
+        }
+
+        fn spins(&self) -> u32 {
+            self.spins.load(Ordering::Relaxed)
+        }
+    }
+
+    impl Clock for SloppyClock {
+        fn now(&self) -> Duration {
+            self.manual.now()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            let oversleep = Duration::from_nanos(self.oversleep.load(Ordering::Relaxed));
+
+            self.manual.sleep(duration + oversleep);
+        }
+
+        fn spin_until(&self, time: Duration) {
+            self.spins.fetch_add(1, Ordering::Relaxed);
+            self.manual.spin_until(time);
+        }
+    }
+
+    fn tick_length() -> Duration {
+        Duration::from_secs_f64(1.0 / TICK_RATE)
+    }
+
+    fn count_updates(timer: &mut Timer) -> u32 {
+        let mut updates = 0;
+
+        while timer.consume_time() {
+            updates += 1;
+        }
+
+        updates
+    }
+
+    #[test]
+    fn accumulates_time_across_ticks() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        clock.advance(tick_length() / 2);
+        timer.tick();
+
+        assert_eq!(count_updates(&mut timer), 0);
+        assert!((timer.blend_factor() - 0.5).abs() < 0.001);
+
+        clock.advance(tick_length() * 2);
+        timer.tick();
+
+        assert_eq!(count_updates(&mut timer), 2);
+        assert!((timer.blend_factor() - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn caps_accumulated_time_at_max_lag() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        clock.advance(Duration::from_secs(10));
+        timer.tick();
+
+        assert_eq!(count_updates(&mut timer), 8);
+        assert_eq!(timer.blend_factor(), 0.0);
+
+        timer.set_max_lag(tick_length() * 60);
+
+        clock.advance(Duration::from_secs(10));
+        timer.tick();
+
+        assert_eq!(count_updates(&mut timer), 60);
+    }
+
+    #[test]
+    fn reset_discards_accumulated_time() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        clock.advance(tick_length() * 3);
+        timer.reset();
+        timer.tick();
+
+        assert_eq!(count_updates(&mut timer), 0);
+        assert_eq!(timer.blend_factor(), 0.0);
+    }
+
+    #[test]
+    fn waits_exactly_one_tick() {
+        let clock = SloppyClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        for _ in 0..3 {
+            timer.tick_until_update_ready();
+
+            assert_eq!(count_updates(&mut timer), 1);
+        }
+
+        assert_eq!(clock.now(), tick_length() * 3);
+        assert_eq!(timer.jitter().max, Duration::ZERO);
+        assert_eq!(timer.jitter().samples, 3);
+    }
+
+    #[test]
+    fn spins_to_correct_oversleeping() {
+        let clock = SloppyClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        clock.set_oversleep(Duration::from_millis(1));
+        timer.set_spin_threshold(Duration::from_millis(2));
+
+        timer.tick_until_update_ready();
+
+        assert_eq!(clock.spins(), 1);
+        assert_eq!(clock.now(), tick_length());
+        assert_eq!(timer.jitter().last, Duration::ZERO);
+    }
+
+    #[test]
+    fn zero_spin_threshold_never_spins() {
+        let clock = SloppyClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        timer.set_spin_threshold(Duration::ZERO);
+
+        for _ in 0..3 {
+            timer.tick_until_update_ready();
+            count_updates(&mut timer);
+        }
+
+        assert_eq!(clock.spins(), 0);
+    }
+
+    #[test]
+    fn tracks_jitter() {
+        let clock = SloppyClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        timer.set_spin_threshold(Duration::ZERO);
+
+        clock.set_oversleep(Duration::from_millis(1));
+        timer.tick_until_update_ready();
+        assert_eq!(count_updates(&mut timer), 1);
+
+        clock.set_oversleep(Duration::from_millis(3));
+        timer.tick_until_update_ready();
+        assert_eq!(count_updates(&mut timer), 1);
+
+        let jitter = timer.jitter();
+
+        assert_eq!(jitter.last, Duration::from_millis(3));
+        assert_eq!(jitter.average, Duration::from_millis(2));
+        assert_eq!(jitter.max, Duration::from_millis(3));
+        assert_eq!(jitter.samples, 2);
+
+        timer.reset_jitter();
+
+        assert_eq!(timer.jitter(), JitterStats::default());
+    }
+
+    #[test]
+    fn vsync_paced_does_not_wait() {
+        let clock = SloppyClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        timer.set_vsync_paced(true);
+        timer.tick_until_update_ready();
+
+        assert_eq!(clock.now(), Duration::ZERO);
+        assert_eq!(clock.spins(), 0);
+        assert_eq!(count_updates(&mut timer), 0);
+        assert_eq!(timer.jitter().samples, 0);
+    }
+
+    #[test]
+    fn scales_game_time() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        timer.set_time_scale(0.5);
+
+        clock.advance(tick_length() * 2);
+        timer.tick();
+        count_updates(&mut timer);
+
+        assert_eq!(timer.game_time(), tick_length().mul_f64(0.5) * 2);
+        assert_eq!(timer.total_time(), tick_length() * 2);
+    }
+
+    /// Runs one tick, returning whether it was frozen and how much game time it took.
+    fn step(timer: &mut Timer, clock: &ManualClock) -> (bool, Duration) {
+        clock.advance(tick_length());
+        timer.tick();
+
+        assert!(timer.consume_time());
+        let result = (timer.is_frozen(), timer.delta());
+        assert!(!timer.consume_time());
+
+        result
+    }
+
+    #[test]
+    fn freezes_for_exactly_n_ticks() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        timer.freeze(2);
+
+        let ticks: Vec<_> = (0..4).map(|_| step(&mut timer, &clock)).collect();
+
+        assert_eq!(
+            ticks,
+            [
+                (true, Duration::ZERO),
+                (true, Duration::ZERO),
+                (false, tick_length()),
+                (false, tick_length()),
+            ]
+        );
+
+        assert_eq!(timer.game_time(), tick_length() * 2);
+    }
+
+    #[test]
+    fn overlapping_freezes_take_the_longest() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        // A shorter freeze doesn't cut a longer one short...
+        timer.freeze(3);
+        assert!(step(&mut timer, &clock).0);
+
+        timer.freeze(1);
+        assert!(step(&mut timer, &clock).0);
+        assert!(step(&mut timer, &clock).0);
+        assert!(!step(&mut timer, &clock).0);
+
+        // ...and a longer freeze extends a shorter one.
+        timer.freeze(1);
+        assert!(step(&mut timer, &clock).0);
+
+        timer.freeze(3);
+        assert!(step(&mut timer, &clock).0);
+        assert!(step(&mut timer, &clock).0);
+        assert!(step(&mut timer, &clock).0);
+        assert!(!step(&mut timer, &clock).0);
+
+        assert_eq!(timer.game_time(), tick_length() * 2);
+    }
+
+    #[test]
+    fn updates_keep_running_while_paused() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        timer.set_paused(true);
+
+        clock.advance(tick_length() * 3);
+        timer.tick();
+
+        let mut updates = 0;
+
+        while timer.consume_time() {
+            assert_eq!(timer.delta(), Duration::ZERO);
+            updates += 1;
+        }
+
+        assert_eq!(updates, 3);
+        assert_eq!(timer.game_time(), Duration::ZERO);
+        assert_eq!(timer.frame_delta(), Duration::ZERO);
+
+        timer.set_paused(false);
+
+        assert_eq!(step(&mut timer, &clock), (false, tick_length()));
+        assert_eq!(timer.game_time(), tick_length());
+    }
+
+    #[test]
+    fn consume_frame_advances_game_time() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(TICK_RATE, clock.clone());
+
+        clock.advance(Duration::from_millis(5));
+        timer.tick();
+        timer.consume_frame();
+
+        assert_eq!(timer.frame_delta(), Duration::from_millis(5));
+        assert_eq!(timer.game_time(), Duration::from_millis(5));
+        assert_eq!(timer.blend_factor(), 0.0);
+    }
+
+    #[test]
+    fn timer_is_send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<Timer>();
     }
 }
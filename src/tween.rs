@@ -0,0 +1,420 @@
+mod easing;
+
+pub use self::easing::*;
+
+use std::time::Duration;
+
+use glam::Vec2;
+
+use crate::graphics::{Color, Rectangle};
+
+/// A value that can be interpolated by a `Tween`.
+pub trait Tweenable: Copy {
+    fn lerp(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn lerp(from: f32, to: f32, t: f32) -> f32 {
+        from + (to - from) * t
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn lerp(from: Vec2, to: Vec2, t: f32) -> Vec2 {
+        from.lerp(to, t)
+    }
+}
+
+impl Tweenable for Color {
+    fn lerp(from: Color, to: Color, t: f32) -> Color {
+        Color::rgba(
+            f32::lerp(from.r, to.r, t),
+            f32::lerp(from.g, to.g, t),
+            f32::lerp(from.b, to.b, t),
+            f32::lerp(from.a, to.a, t),
+        )
+    }
+}
+
+impl Tweenable for Rectangle {
+    fn lerp(from: Rectangle, to: Rectangle, t: f32) -> Rectangle {
+        Rectangle::new(
+            f32::lerp(from.x, to.x, t),
+            f32::lerp(from.y, to.y, t),
+            f32::lerp(from.width, to.width, t),
+            f32::lerp(from.height, to.height, t),
+        )
+    }
+}
+
+/// Something that plays out over time, and can be combined into sequences and groups.
+///
+/// Animations are advanced by an explicit amount of time, rather than reading the clock,
+/// so calling `update` with `Timer::delta` from `EventHandler::update` keeps them in
+/// step with the fixed-rate game loop (and with time scaling and pausing).
+pub trait Animation {
+    /// Advances the animation, returning the amount of time that was left over after
+    /// it finished. This will be zero if the animation is still running.
+    fn update(&mut self, dt: Duration) -> Duration;
+
+    fn is_finished(&self) -> bool;
+
+    /// Resets the animation back to the start.
+    fn restart(&mut self);
+}
+
+/// Interpolates a value between two points over a period of time.
+///
+/// The current value can be read with `value`, or received via `on_update` - the latter
+/// is useful when the tween is part of a `Sequence` or `Parallel` group.
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    value: T,
+    duration: Duration,
+    easing: Easing,
+
+    passes: Option<u32>,
+    yoyo: bool,
+
+    elapsed: Duration,
+    pass: u32,
+    finished: bool,
+
+    on_update: Option<Box<dyn FnMut(T)>>,
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl<T> Tween<T>
+where
+    T: Tweenable,
+{
+    pub fn new(from: T, to: T, duration: Duration) -> Tween<T> {
+        Tween {
+            from,
+            to,
+            value: from,
+            duration,
+            easing: Easing::Linear,
+
+            passes: Some(1),
+            yoyo: false,
+
+            elapsed: Duration::ZERO,
+            pass: 0,
+            finished: false,
+
+            on_update: None,
+            on_complete: None,
+        }
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self.value = self.sample();
+        self
+    }
+
+    /// Plays the tween again after it finishes, the given number of times.
+    pub fn repeat(mut self, times: u32) -> Self {
+        self.passes = Some(times.saturating_add(1));
+        self
+    }
+
+    /// Plays the tween on a loop, until it is restarted or dropped.
+    pub fn repeat_forever(mut self) -> Self {
+        self.passes = None;
+        self
+    }
+
+    /// Plays every other repetition in reverse, so that the value goes back and forth.
+    pub fn yoyo(mut self, yoyo: bool) -> Self {
+        self.yoyo = yoyo;
+        self
+    }
+
+    /// Sets a function to be called with the new value each time the tween updates.
+    pub fn on_update(mut self, callback: impl FnMut(T) + 'static) -> Self {
+        self.on_update = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets a function to be called when the tween finishes.
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns how far through the current repetition the tween is, from 0.0 to 1.0,
+    /// before easing is applied.
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            1.0
+        } else {
+            (self.elapsed.as_secs_f64() / self.duration.as_secs_f64()) as f32
+        }
+    }
+
+    fn sample(&self) -> T {
+        let reversed = self.yoyo && self.pass % 2 == 1;
+        let t = if reversed {
+            1.0 - self.progress()
+        } else {
+            self.progress()
+        };
+
+        T::lerp(self.from, self.to, self.easing.apply(t))
+    }
+}
+
+impl<T> Animation for Tween<T>
+where
+    T: Tweenable,
+{
+    fn update(&mut self, dt: Duration) -> Duration {
+        if self.finished {
+            return dt;
+        }
+
+        let mut remaining = dt;
+
+        loop {
+            let left_in_pass = self.duration - self.elapsed;
+
+            if remaining < left_in_pass {
+                self.elapsed += remaining;
+                remaining = Duration::ZERO;
+                break;
+            }
+
+            remaining -= left_in_pass;
+            self.elapsed = self.duration;
+
+            let has_next_pass = match self.passes {
+                Some(passes) => self.pass + 1 < passes,
+                None => true,
+            };
+
+            if !has_next_pass {
+                self.finished = true;
+                break;
+            }
+
+            // An empty tween that loops forever would never consume any time.
+            if self.duration.is_zero() {
+                remaining = Duration::ZERO;
+                break;
+            }
+
+            self.pass += 1;
+            self.elapsed = Duration::ZERO;
+        }
+
+        self.value = self.sample();
+
+        if let Some(on_update) = &mut self.on_update {
+            on_update(self.value);
+        }
+
+        if self.finished {
+            if let Some(on_complete) = &mut self.on_complete {
+                on_complete();
+            }
+        }
+
+        remaining
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.pass = 0;
+        self.finished = false;
+        self.value = self.sample();
+    }
+}
+
+/// Waits for a period of time. This is useful for adding gaps to a `Sequence`.
+pub struct Delay {
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl Delay {
+    pub fn new(duration: Duration) -> Delay {
+        Delay {
+            duration,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl Animation for Delay {
+    fn update(&mut self, dt: Duration) -> Duration {
+        let left = self.duration - self.elapsed;
+        let used = dt.min(left);
+
+        self.elapsed += used;
+
+        dt - used
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+}
+
+/// Calls a function once, without taking any time. This is useful for triggering
+/// events partway through a `Sequence`.
+pub struct Call {
+    callback: Box<dyn FnMut()>,
+    called: bool,
+}
+
+impl Call {
+    pub fn new(callback: impl FnMut() + 'static) -> Call {
+        Call {
+            callback: Box::new(callback),
+            called: false,
+        }
+    }
+}
+
+impl Animation for Call {
+    fn update(&mut self, dt: Duration) -> Duration {
+        if !self.called {
+            self.called = true;
+            (self.callback)();
+        }
+
+        dt
+    }
+
+    fn is_finished(&self) -> bool {
+        self.called
+    }
+
+    fn restart(&mut self) {
+        self.called = false;
+    }
+}
+
+/// Plays a list of animations one after another.
+///
+/// Any time left over when one animation finishes is passed on to the next, so the
+/// total duration does not depend on how the updates line up.
+pub struct Sequence {
+    animations: Vec<Box<dyn Animation>>,
+    current: usize,
+}
+
+impl Sequence {
+    pub fn new() -> Sequence {
+        Sequence {
+            animations: Vec::new(),
+            current: 0,
+        }
+    }
+
+    pub fn then(mut self, animation: impl Animation + 'static) -> Self {
+        self.animations.push(Box::new(animation));
+        self
+    }
+
+    pub fn wait(self, duration: Duration) -> Self {
+        self.then(Delay::new(duration))
+    }
+
+    pub fn call(self, callback: impl FnMut() + 'static) -> Self {
+        self.then(Call::new(callback))
+    }
+}
+
+impl Animation for Sequence {
+    fn update(&mut self, mut dt: Duration) -> Duration {
+        while let Some(animation) = self.animations.get_mut(self.current) {
+            dt = animation.update(dt);
+
+            if !animation.is_finished() {
+                return Duration::ZERO;
+            }
+
+            self.current += 1;
+        }
+
+        dt
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current >= self.animations.len()
+    }
+
+    fn restart(&mut self) {
+        self.current = 0;
+
+        for animation in &mut self.animations {
+            animation.restart();
+        }
+    }
+}
+
+/// Plays a group of animations at the same time, finishing when all of them have.
+pub struct Parallel {
+    animations: Vec<Box<dyn Animation>>,
+}
+
+impl Parallel {
+    pub fn new() -> Parallel {
+        Parallel {
+            animations: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, animation: impl Animation + 'static) -> Self {
+        self.animations.push(Box::new(animation));
+        self
+    }
+}
+
+impl Animation for Parallel {
+    fn update(&mut self, dt: Duration) -> Duration {
+        let mut remaining = dt;
+
+        for animation in &mut self.animations {
+            if !animation.is_finished() {
+                remaining = remaining.min(animation.update(dt));
+            }
+        }
+
+        if self.is_finished() {
+            remaining
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.animations.iter().all(|a| a.is_finished())
+    }
+
+    fn restart(&mut self) {
+        for animation in &mut self.animations {
+            animation.restart();
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::f32::consts::PI;
+
+/// The standard Robert Penner easing functions.
+///
+/// See <https://easings.net> for a visual reference.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+
+    SineIn,
+    SineOut,
+    SineInOut,
+
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+
+    CircIn,
+    CircOut,
+    CircInOut,
+
+    BackIn,
+    BackOut,
+    BackInOut,
+
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    /// Maps a linear progress value from 0.0 to 1.0 onto the easing curve.
+    ///
+    /// The result will be 0.0 at the start and 1.0 at the end, but some curves (like
+    /// `Back` and `Elastic`) overshoot in between.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+
+            Easing::QuadIn => pow_in(t, 2),
+            Easing::QuadOut => pow_out(t, 2),
+            Easing::QuadInOut => pow_in_out(t, 2),
+
+            Easing::CubicIn => pow_in(t, 3),
+            Easing::CubicOut => pow_out(t, 3),
+            Easing::CubicInOut => pow_in_out(t, 3),
+
+            Easing::QuartIn => pow_in(t, 4),
+            Easing::QuartOut => pow_out(t, 4),
+            Easing::QuartInOut => pow_in_out(t, 4),
+
+            Easing::QuintIn => pow_in(t, 5),
+            Easing::QuintOut => pow_out(t, 5),
+            Easing::QuintInOut => pow_in_out(t, 5),
+
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+
+            Easing::ExpoIn => {
+                if t == 0.0 {
+                    0.0
+                } else {
+                    f32::powf(2.0, 10.0 * t - 10.0)
+                }
+            }
+            Easing::ExpoOut => {
+                if t == 1.0 {
+                    1.0
+                } else {
+                    1.0 - f32::powf(2.0, -10.0 * t)
+                }
+            }
+            Easing::ExpoInOut => {
+                if t == 0.0 {
+                    0.0
+                } else if t == 1.0 {
+                    1.0
+                } else if t < 0.5 {
+                    f32::powf(2.0, 20.0 * t - 10.0) / 2.0
+                } else {
+                    (2.0 - f32::powf(2.0, -20.0 * t + 10.0)) / 2.0
+                }
+            }
+
+            Easing::CircIn => 1.0 - (1.0 - t * t).sqrt(),
+            Easing::CircOut => (1.0 - (t - 1.0).powi(2)).sqrt(),
+            Easing::CircInOut => {
+                if t < 0.5 {
+                    (1.0 - (1.0 - (2.0 * t).powi(2)).sqrt()) / 2.0
+                } else {
+                    ((1.0 - (-2.0 * t + 2.0).powi(2)).sqrt() + 1.0) / 2.0
+                }
+            }
+
+            Easing::BackIn => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+
+                C3 * t * t * t - C1 * t * t
+            }
+            Easing::BackOut => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+            Easing::BackInOut => {
+                const C2: f32 = 1.70158 * 1.525;
+
+                if t < 0.5 {
+                    ((2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2)) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
+                }
+            }
+
+            Easing::ElasticIn => {
+                const C4: f32 = (2.0 * PI) / 3.0;
+
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    -f32::powf(2.0, 10.0 * t - 10.0) * ((t * 10.0 - 10.75) * C4).sin()
+                }
+            }
+            Easing::ElasticOut => {
+                const C4: f32 = (2.0 * PI) / 3.0;
+
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    f32::powf(2.0, -10.0 * t) * ((t * 10.0 - 0.75) * C4).sin() + 1.0
+                }
+            }
+            Easing::ElasticInOut => {
+                const C5: f32 = (2.0 * PI) / 4.5;
+
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    -(f32::powf(2.0, 20.0 * t - 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0
+                } else {
+                    (f32::powf(2.0, -20.0 * t + 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0
+                        + 1.0
+                }
+            }
+
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => {
+                if t < 0.5 {
+                    (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+                } else {
+                    (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+                }
+            }
+        }
+    }
+}
+
+fn pow_in(t: f32, power: i32) -> f32 {
+    t.powi(power)
+}
+
+fn pow_out(t: f32, power: i32) -> f32 {
+    1.0 - (1.0 - t).powi(power)
+}
+
+fn pow_in_out(t: f32, power: i32) -> f32 {
+    if t < 0.5 {
+        f32::powi(2.0, power - 1) * t.powi(power)
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(power) / 2.0
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
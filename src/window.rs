@@ -1,85 +1,301 @@
+mod cursor;
+mod dialog;
+mod display;
+
+use std::cell::{Cell, RefCell};
 use std::ffi::{CStr, CString};
-use std::mem::MaybeUninit;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
+use sdl3_sys::clipboard::*;
 use sdl3_sys::error::*;
 use sdl3_sys::events::*;
+use sdl3_sys::hints::*;
 use sdl3_sys::init::*;
 use sdl3_sys::keyboard::*;
+use sdl3_sys::mouse::*;
+use sdl3_sys::pixels::*;
+use sdl3_sys::stdinc::*;
+use sdl3_sys::surface::*;
 use sdl3_sys::version::*;
 use sdl3_sys::video::*;
 
+use glam::Vec2;
 use glow::Context;
 
+use crate::graphics::ImageData;
+
+pub use self::cursor::*;
+pub use self::dialog::*;
+pub use self::display::*;
+
 static SDL_INIT: AtomicBool = AtomicBool::new(false);
 
+/// Initializes SDL, if it has not been initialized already.
+///
+/// This is called automatically when the first window is created, but can be called
+/// earlier if you need to access things like `Display::all` before then.
+pub fn init() {
+    if SDL_INIT.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    unsafe {
+        if !SDL_Init(SDL_INIT_VIDEO | SDL_INIT_EVENTS | SDL_INIT_GAMEPAD) {
+            sdl_panic!();
+        }
+
+        // Closing a window is reported as a `WindowCloseRequested` event instead,
+        // so that the game has a chance to cancel it.
+        SDL_SetHint(SDL_HINT_QUIT_ON_LAST_WINDOW_CLOSE, c"0".as_ptr());
+
+        SDL_GL_SetAttribute(SDL_GL_CONTEXT_MAJOR_VERSION, 3);
+        SDL_GL_SetAttribute(SDL_GL_CONTEXT_MINOR_VERSION, 3);
+        SDL_GL_SetAttribute(SDL_GL_CONTEXT_PROFILE_MASK, SDL_GL_CONTEXT_PROFILE_CORE);
+        SDL_GL_SetAttribute(SDL_GL_CONTEXT_FLAGS, SDL_GL_CONTEXT_FORWARD_COMPATIBLE_FLAG);
+        SDL_GL_SetAttribute(SDL_GL_DOUBLEBUFFER, 1);
+
+        SDL_DisableScreenSaver();
+
+        let version = SDL_GetVersion();
+
+        println!(
+            "SDL Version: {}.{}.{}",
+            SDL_VERSIONNUM_MAJOR(version),
+            SDL_VERSIONNUM_MINOR(version),
+            SDL_VERSIONNUM_MICRO(version),
+        );
+    }
+}
+
+/// A unique ID for a window, which can be used to tell which window an event
+/// belongs to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct WindowID(SDL_WindowID);
+
+impl WindowID {
+    pub fn from_raw(id: SDL_WindowID) -> WindowID {
+        WindowID(id)
+    }
+}
+
+/// An OpenGL context, which is shared between all of the windows created from it.
+struct GlContext {
+    raw: SDL_GLContext,
+    current_window: Cell<*mut SDL_Window>,
+
+    /// Every window that is still using the context, so that another one can be made
+    /// current when the current window is destroyed.
+    windows: RefCell<Vec<*mut SDL_Window>>,
+}
+
+impl Drop for GlContext {
+    fn drop(&mut self) {
+        unsafe {
+            SDL_GL_DestroyContext(self.raw);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FullscreenMode {
+    Windowed,
+
+    /// A borderless window that covers the whole display, without changing the display mode.
+    Borderless,
+
+    /// Switches the display to the mode closest to the window's size, falling back to
+    /// `Borderless` if there isn't one.
+    Exclusive,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VSync {
+    Off,
+    On,
+
+    /// Syncs to the display when running fast enough, and tears otherwise. Falls back to
+    /// `On` if the driver does not support it.
+    Adaptive,
+}
+
+pub struct WindowConfig {
+    title: String,
+    width: i32,
+    height: i32,
+
+    fullscreen: FullscreenMode,
+    vsync: VSync,
+    resizable: bool,
+    always_on_top: bool,
+    min_size: Option<(i32, i32)>,
+    max_size: Option<(i32, i32)>,
+    position: Option<(i32, i32)>,
+    icon: Option<String>,
+}
+
+impl WindowConfig {
+    pub fn new(title: &str, width: i32, height: i32) -> WindowConfig {
+        WindowConfig {
+            title: title.to_owned(),
+            width,
+            height,
+
+            fullscreen: FullscreenMode::Windowed,
+            vsync: VSync::On,
+            resizable: true,
+            always_on_top: false,
+            min_size: None,
+            max_size: None,
+            position: None,
+            icon: None,
+        }
+    }
+
+    pub fn fullscreen(mut self, fullscreen: FullscreenMode) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn vsync(mut self, vsync: VSync) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    pub fn min_size(mut self, width: i32, height: i32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    pub fn max_size(mut self, width: i32, height: i32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// Sets the initial position of the window. If this is not set, the window will be
+    /// centered on the primary display.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Sets the window icon, loaded from a PNG file via `nova::fs`.
+    pub fn icon(mut self, path: &str) -> Self {
+        self.icon = Some(path.to_owned());
+        self
+    }
+}
+
 pub struct Window {
     window: *mut SDL_Window,
-    gl: SDL_GLContext,
+    context: ManuallyDrop<Rc<GlContext>>,
 
     visible: bool,
+    cursor: Option<Cursor>,
 }
 
 impl Window {
-    pub fn new(title: &str, width: i32, height: i32) -> Window {
-        unsafe {
-            if SDL_INIT.load(Ordering::Relaxed) {
-                panic!("SDL already initialized");
-            }
-
-            if !SDL_Init(SDL_INIT_VIDEO | SDL_INIT_EVENTS | SDL_INIT_GAMEPAD) {
-                sdl_panic!();
-            }
+    pub fn new(config: &WindowConfig) -> Window {
+        Window::create(config, None)
+    }
 
-            SDL_INIT.store(true, Ordering::Relaxed);
+    /// Creates a secondary window, which shares its OpenGL context with an existing one.
+    ///
+    /// This means that the same `Graphics` (and any textures, shaders, etc. created
+    /// with it) can be used to draw to either window.
+    pub fn new_shared(config: &WindowConfig, share_with: &Window) -> Window {
+        Window::create(config, Some(Rc::clone(&share_with.context)))
+    }
 
-            SDL_GL_SetAttribute(SDL_GL_CONTEXT_MAJOR_VERSION, 3);
-            SDL_GL_SetAttribute(SDL_GL_CONTEXT_MINOR_VERSION, 3);
-            SDL_GL_SetAttribute(SDL_GL_CONTEXT_PROFILE_MASK, SDL_GL_CONTEXT_PROFILE_CORE);
-            SDL_GL_SetAttribute(SDL_GL_CONTEXT_FLAGS, SDL_GL_CONTEXT_FORWARD_COMPATIBLE_FLAG);
-            SDL_GL_SetAttribute(SDL_GL_DOUBLEBUFFER, 1);
+    fn create(config: &WindowConfig, context: Option<Rc<GlContext>>) -> Window {
+        init();
 
-            let c_title = CString::new(title).unwrap();
+        unsafe {
+            let c_title = CString::new(config.title.as_str()).unwrap();
 
-            let window = SDL_CreateWindow(
-                c_title.as_ptr(),
-                width,
-                height,
-                SDL_WINDOW_OPENGL | SDL_WINDOW_RESIZABLE | SDL_WINDOW_HIDDEN,
-            );
+            let mut flags = SDL_WINDOW_OPENGL | SDL_WINDOW_HIDDEN | SDL_WINDOW_HIGH_PIXEL_DENSITY;
 
-            if window.is_null() {
-                sdl_panic!();
+            if config.resizable {
+                flags |= SDL_WINDOW_RESIZABLE;
             }
 
-            SDL_DisableScreenSaver();
+            if config.always_on_top {
+                flags |= SDL_WINDOW_ALWAYS_ON_TOP;
+            }
 
-            let gl = SDL_GL_CreateContext(window);
+            let window = SDL_CreateWindow(c_title.as_ptr(), config.width, config.height, flags);
 
-            if gl.is_null() {
+            if window.is_null() {
                 sdl_panic!();
             }
 
-            SDL_GL_SetSwapInterval(1);
+            let context = match context {
+                Some(context) => context,
+                None => {
+                    let raw = SDL_GL_CreateContext(window);
 
-            let version = SDL_GetVersion();
+                    if raw.is_null() {
+                        sdl_panic!();
+                    }
 
-            println!(
-                "SDL Version: {}.{}.{}",
-                SDL_VERSIONNUM_MAJOR(version),
-                SDL_VERSIONNUM_MINOR(version),
-                SDL_VERSIONNUM_MICRO(version),
-            );
+                    Rc::new(GlContext {
+                        raw,
+                        current_window: Cell::new(window),
+                        windows: RefCell::new(Vec::new()),
+                    })
+                }
+            };
+
+            context.windows.borrow_mut().push(window);
 
-            Window {
+            let mut window = Window {
                 window,
-                gl,
+                context: ManuallyDrop::new(context),
 
                 visible: false,
+                cursor: None,
+            };
+
+            window.set_vsync(config.vsync);
+
+            if let Some((width, height)) = config.min_size {
+                window.set_min_size(width, height);
+            }
+
+            if let Some((width, height)) = config.max_size {
+                window.set_max_size(width, height);
+            }
+
+            match config.position {
+                Some((x, y)) => window.set_position(x, y),
+                None => window.center(),
             }
+
+            if let Some(icon) = &config.icon {
+                window.set_icon(&ImageData::from_file(icon, false));
+            }
+
+            window.set_fullscreen(config.fullscreen);
+
+            window
         }
     }
 
+    /// Returns the size of the window in pixels. This is the size that should be used
+    /// for rendering.
     pub fn size(&self) -> (u32, u32) {
         unsafe {
             let mut w = 0;
@@ -91,6 +307,58 @@ impl Window {
         }
     }
 
+    /// Returns the size of the window in logical coordinates, which are the units used
+    /// for mouse positions and window sizing.
+    ///
+    /// On high-DPI displays, this may be smaller than the size in pixels.
+    pub fn logical_size(&self) -> (u32, u32) {
+        unsafe {
+            let mut w = 0;
+            let mut h = 0;
+
+            SDL_GetWindowSize(self.window, &mut w, &mut h);
+
+            (w as u32, h as u32)
+        }
+    }
+
+    /// Returns the number of pixels per logical coordinate.
+    pub fn pixel_density(&self) -> f32 {
+        unsafe {
+            let density = SDL_GetWindowPixelDensity(self.window);
+
+            if density > 0.0 {
+                density
+            } else {
+                1.0
+            }
+        }
+    }
+
+    /// Returns the scale that content should be displayed at, combining the pixel
+    /// density with the user's display scaling setting.
+    pub fn display_scale(&self) -> f32 {
+        unsafe {
+            let scale = SDL_GetWindowDisplayScale(self.window);
+
+            if scale > 0.0 {
+                scale
+            } else {
+                1.0
+            }
+        }
+    }
+
+    /// Converts a position in logical coordinates (e.g. from a mouse event) to pixels.
+    pub fn to_pixels(&self, position: Vec2) -> Vec2 {
+        position * self.pixel_density()
+    }
+
+    /// Converts a position in pixels to logical coordinates.
+    pub fn to_logical(&self, position: Vec2) -> Vec2 {
+        position / self.pixel_density()
+    }
+
     pub fn load_gl(&self) -> Context {
         unsafe {
             Context::from_loader_function_cstr(|s| {
@@ -117,7 +385,26 @@ impl Window {
         }
     }
 
+    pub fn id(&self) -> WindowID {
+        unsafe { WindowID(SDL_GetWindowID(self.window)) }
+    }
+
+    /// Makes this window the target of the shared OpenGL context, if it isn't already.
+    pub(crate) fn make_current(&self) {
+        if self.context.current_window.get() != self.window {
+            unsafe {
+                if !SDL_GL_MakeCurrent(self.window, self.context.raw) {
+                    sdl_panic!();
+                }
+            }
+
+            self.context.current_window.set(self.window);
+        }
+    }
+
     pub fn present(&mut self) {
+        self.make_current();
+
         unsafe {
             SDL_GL_SwapWindow(self.window);
 
@@ -136,6 +423,278 @@ impl Window {
         }
     }
 
+    pub fn set_size(&mut self, width: i32, height: i32) {
+        unsafe {
+            SDL_SetWindowSize(self.window, width, height);
+        }
+    }
+
+    pub fn set_min_size(&mut self, width: i32, height: i32) {
+        unsafe {
+            SDL_SetWindowMinimumSize(self.window, width, height);
+        }
+    }
+
+    pub fn set_max_size(&mut self, width: i32, height: i32) {
+        unsafe {
+            SDL_SetWindowMaximumSize(self.window, width, height);
+        }
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        unsafe {
+            let mut x = 0;
+            let mut y = 0;
+
+            SDL_GetWindowPosition(self.window, &mut x, &mut y);
+
+            (x, y)
+        }
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        unsafe {
+            SDL_SetWindowPosition(self.window, x, y);
+        }
+    }
+
+    pub fn center(&mut self) {
+        unsafe {
+            SDL_SetWindowPosition(self.window, SDL_WINDOWPOS_CENTERED, SDL_WINDOWPOS_CENTERED);
+        }
+    }
+
+    pub fn set_resizable(&mut self, resizable: bool) {
+        unsafe {
+            SDL_SetWindowResizable(self.window, resizable);
+        }
+    }
+
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        unsafe {
+            SDL_SetWindowAlwaysOnTop(self.window, always_on_top);
+        }
+    }
+
+    pub fn fullscreen(&self) -> FullscreenMode {
+        unsafe {
+            if SDL_GetWindowFlags(self.window) & SDL_WINDOW_FULLSCREEN == 0 {
+                FullscreenMode::Windowed
+            } else if SDL_GetWindowFullscreenMode(self.window).is_null() {
+                FullscreenMode::Borderless
+            } else {
+                FullscreenMode::Exclusive
+            }
+        }
+    }
+
+    pub fn set_fullscreen(&mut self, fullscreen: FullscreenMode) {
+        unsafe {
+            match fullscreen {
+                FullscreenMode::Windowed => {
+                    SDL_SetWindowFullscreen(self.window, false);
+                }
+
+                FullscreenMode::Borderless => {
+                    SDL_SetWindowFullscreenMode(self.window, std::ptr::null());
+                    SDL_SetWindowFullscreen(self.window, true);
+                }
+
+                FullscreenMode::Exclusive => {
+                    let (width, height) = self.logical_size();
+
+                    self.set_fullscreen_mode(DisplayMode {
+                        display: self.display(),
+                        width: width as i32,
+                        height: height as i32,
+                        pixel_density: 1.0,
+                        refresh_rate: 0.0,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns the display mode used for exclusive fullscreen, or `None` if the window
+    /// is using borderless fullscreen.
+    pub fn fullscreen_mode(&self) -> Option<DisplayMode> {
+        unsafe {
+            let mode = SDL_GetWindowFullscreenMode(self.window).as_ref()?;
+
+            Some(DisplayMode {
+                display: Display::from_raw(mode.displayID),
+                width: mode.w,
+                height: mode.h,
+                pixel_density: mode.pixel_density,
+                refresh_rate: mode.refresh_rate,
+            })
+        }
+    }
+
+    /// Switches to exclusive fullscreen on the mode's display, using the closest
+    /// available match to the given mode.
+    ///
+    /// If there is no suitable mode (e.g. the display has been disconnected, or the mode
+    /// is larger than any the display supports), this falls back to borderless fullscreen
+    /// on the window's current display.
+    pub fn set_fullscreen_mode(&mut self, mode: DisplayMode) {
+        let Some(raw) = mode.to_raw() else {
+            self.set_fullscreen(FullscreenMode::Borderless);
+            return;
+        };
+
+        if self.display() != mode.display {
+            self.move_to_display(mode.display);
+        }
+
+        unsafe {
+            SDL_SetWindowFullscreenMode(self.window, &raw);
+            SDL_SetWindowFullscreen(self.window, true);
+        }
+    }
+
+    /// Returns the display that the center of the window is on.
+    pub fn display(&self) -> Display {
+        unsafe { Display::from_raw(SDL_GetDisplayForWindow(self.window)) }
+    }
+
+    /// Centers the window on the given display. If the window is fullscreen, it will
+    /// become fullscreen on the new display instead.
+    pub fn move_to_display(&mut self, display: Display) {
+        let pos = SDL_WINDOWPOS_CENTERED_DISPLAY(display.id());
+
+        unsafe {
+            SDL_SetWindowPosition(self.window, pos, pos);
+        }
+    }
+
+    pub fn vsync(&self) -> VSync {
+        self.make_current();
+
+        unsafe {
+            let mut interval = 0;
+
+            SDL_GL_GetSwapInterval(&mut interval);
+
+            match interval {
+                0 => VSync::Off,
+                i if i < 0 => VSync::Adaptive,
+                _ => VSync::On,
+            }
+        }
+    }
+
+    pub fn set_vsync(&mut self, vsync: VSync) {
+        self.make_current();
+
+        unsafe {
+            match vsync {
+                VSync::Off => {
+                    SDL_GL_SetSwapInterval(0);
+                }
+
+                VSync::On => {
+                    SDL_GL_SetSwapInterval(1);
+                }
+
+                VSync::Adaptive => {
+                    if !SDL_GL_SetSwapInterval(-1) {
+                        SDL_GL_SetSwapInterval(1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sets the window icon from RGBA image data. The data should not be premultiplied.
+    pub fn set_icon(&mut self, image: &ImageData) {
+        unsafe {
+            let surface = SDL_CreateSurfaceFrom(
+                image.width,
+                image.height,
+                SDL_PIXELFORMAT_RGBA32,
+                image.pixels.as_ptr() as *mut _,
+                image.width * 4,
+            );
+
+            if surface.is_null() {
+                sdl_panic!();
+            }
+
+            SDL_SetWindowIcon(self.window, surface);
+            SDL_DestroySurface(surface);
+        }
+    }
+
+    pub fn show_cursor(&mut self) {
+        unsafe {
+            SDL_ShowCursor();
+        }
+    }
+
+    pub fn hide_cursor(&mut self) {
+        unsafe {
+            SDL_HideCursor();
+        }
+    }
+
+    pub fn is_cursor_visible(&self) -> bool {
+        unsafe { SDL_CursorVisible() }
+    }
+
+    /// Sets the cursor that is displayed while the mouse is over the window.
+    pub fn set_cursor(&mut self, cursor: &Cursor) {
+        unsafe {
+            if !SDL_SetCursor(cursor.raw()) {
+                sdl_panic!();
+            }
+        }
+
+        // The cursor needs to stay alive for as long as it's active.
+        self.cursor = Some(cursor.clone());
+    }
+
+    pub fn reset_cursor(&mut self) {
+        unsafe {
+            SDL_SetCursor(SDL_GetDefaultCursor());
+        }
+
+        self.cursor = None;
+    }
+
+    /// Enables or disables relative mouse mode.
+    ///
+    /// While enabled, the cursor is hidden and locked to the window, and only the
+    /// `delta` of `MouseMotion` events (and `Input::mouse_delta`) will change. This
+    /// is useful for FPS-style or twin-stick aiming.
+    ///
+    /// Returns whether the mode was changed, as not all platforms support relative mode.
+    pub fn set_relative_mouse_mode(&mut self, enabled: bool) -> bool {
+        unsafe { SDL_SetWindowRelativeMouseMode(self.window, enabled) }
+    }
+
+    pub fn relative_mouse_mode(&self) -> bool {
+        unsafe { SDL_GetWindowRelativeMouseMode(self.window) }
+    }
+
+    /// Confines the cursor to the window while it is focused.
+    pub fn set_mouse_grab(&mut self, grabbed: bool) {
+        unsafe {
+            SDL_SetWindowMouseGrab(self.window, grabbed);
+        }
+    }
+
+    pub fn mouse_grab(&self) -> bool {
+        unsafe { SDL_GetWindowMouseGrab(self.window) }
+    }
+
+    /// Moves the cursor to a position within the window, in logical coordinates.
+    pub fn warp_mouse(&mut self, position: Vec2) {
+        unsafe {
+            SDL_WarpMouseInWindow(self.window, position.x, position.y);
+        }
+    }
+
     pub fn start_text_input(&mut self) {
         unsafe {
             SDL_StartTextInput(self.window);
@@ -147,17 +706,102 @@ impl Window {
             SDL_StopTextInput(self.window);
         }
     }
+
+    pub fn clipboard_text(&self) -> Option<String> {
+        unsafe {
+            let raw = SDL_GetClipboardText();
+
+            if raw.is_null() {
+                return None;
+            }
+
+            let text = CStr::from_ptr(raw).to_string_lossy().into_owned();
+
+            SDL_free(raw as *mut _);
+
+            if text.is_empty() {
+                None
+            } else {
+                Some(text)
+            }
+        }
+    }
+
+    /// Places text on the clipboard, returning whether it succeeded.
+    ///
+    /// This can fail if the text contains a NUL byte, or if the platform does not
+    /// currently allow clipboard access (e.g. on Wayland, when the window is not focused).
+    pub fn set_clipboard_text(&mut self, text: &str) -> bool {
+        let Ok(c_text) = CString::new(text) else {
+            return false;
+        };
+
+        unsafe { SDL_SetClipboardText(c_text.as_ptr()) }
+    }
+
+    pub fn has_clipboard_text(&self) -> bool {
+        unsafe { SDL_HasClipboardText() }
+    }
 }
 
 impl Drop for Window {
     fn drop(&mut self) {
         unsafe {
-            SDL_GL_DestroyContext(self.gl);
+            self.context
+                .windows
+                .borrow_mut()
+                .retain(|w| *w != self.window);
+
+            let survivor = self.context.windows.borrow().last().copied();
+
+            match survivor {
+                // Destroying the current window leaves no context current, so switch to
+                // one of the remaining windows first.
+                Some(survivor) => {
+                    if self.context.current_window.get() == self.window {
+                        let current = if SDL_GL_MakeCurrent(survivor, self.context.raw) {
+                            survivor
+                        } else {
+                            std::ptr::null_mut()
+                        };
+
+                        self.context.current_window.set(current);
+                    }
+                }
+
+                // This is the last window using the context, so the context has to be
+                // destroyed first, while its drawable is still valid.
+                None => {
+                    if self.context.current_window.get() != self.window {
+                        SDL_GL_MakeCurrent(self.window, self.context.raw);
+                    }
+                }
+            }
+
+            ManuallyDrop::drop(&mut self.context);
+
             SDL_DestroyWindow(self.window);
         }
     }
 }
 
+/// Returns the SDL event type stored in `event_type`, registering a new one the first
+/// time this is called.
+///
+/// Types should be registered before anything could push an event of that type (e.g.
+/// from another thread), so that the event loop is guaranteed to recognize it.
+pub(crate) fn register_event_type(event_type: &OnceLock<u32>) -> u32 {
+    *event_type.get_or_init(|| unsafe {
+        let event_type = SDL_RegisterEvents(1);
+
+        if event_type == 0 {
+            sdl_panic!();
+        }
+
+        event_type
+    })
+}
+
 pub(crate) unsafe fn get_err() -> String {
     unsafe {
         CStr::from_ptr(SDL_GetError())
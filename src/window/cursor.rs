@@ -0,0 +1,120 @@
+use std::{fmt, rc::Rc};
+
+use sdl3_sys::mouse::*;
+use sdl3_sys::pixels::*;
+use sdl3_sys::surface::*;
+
+use crate::graphics::ImageData;
+
+use super::sdl_panic;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SystemCursor {
+    Default,
+    Text,
+    Wait,
+    Crosshair,
+    Progress,
+    NotAllowed,
+    Pointer,
+    Move,
+    ResizeHorizontal,
+    ResizeVertical,
+    ResizeDiagonalNwse,
+    ResizeDiagonalNesw,
+}
+
+impl SystemCursor {
+    fn to_raw(self) -> SDL_SystemCursor {
+        match self {
+            SystemCursor::Default => SDL_SYSTEM_CURSOR_DEFAULT,
+            SystemCursor::Text => SDL_SYSTEM_CURSOR_TEXT,
+            SystemCursor::Wait => SDL_SYSTEM_CURSOR_WAIT,
+            SystemCursor::Crosshair => SDL_SYSTEM_CURSOR_CROSSHAIR,
+            SystemCursor::Progress => SDL_SYSTEM_CURSOR_PROGRESS,
+            SystemCursor::NotAllowed => SDL_SYSTEM_CURSOR_NOT_ALLOWED,
+            SystemCursor::Pointer => SDL_SYSTEM_CURSOR_POINTER,
+            SystemCursor::Move => SDL_SYSTEM_CURSOR_MOVE,
+            SystemCursor::ResizeHorizontal => SDL_SYSTEM_CURSOR_EW_RESIZE,
+            SystemCursor::ResizeVertical => SDL_SYSTEM_CURSOR_NS_RESIZE,
+            SystemCursor::ResizeDiagonalNwse => SDL_SYSTEM_CURSOR_NWSE_RESIZE,
+            SystemCursor::ResizeDiagonalNesw => SDL_SYSTEM_CURSOR_NESW_RESIZE,
+        }
+    }
+}
+
+/// A mouse cursor, which can be applied with `Window::set_cursor`.
+#[derive(Clone)]
+pub struct Cursor(Rc<CursorInner>);
+
+impl PartialEq for Cursor {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cursor(...)")
+    }
+}
+
+struct CursorInner {
+    handle: *mut SDL_Cursor,
+}
+
+impl Cursor {
+    /// Creates a cursor from RGBA image data. The data should not be premultiplied.
+    ///
+    /// `hot_x` and `hot_y` are the position within the image that acts as the tip
+    /// of the cursor.
+    pub fn from_image(image: &ImageData, hot_x: i32, hot_y: i32) -> Cursor {
+        unsafe {
+            let surface = SDL_CreateSurfaceFrom(
+                image.width,
+                image.height,
+                SDL_PIXELFORMAT_RGBA32,
+                image.pixels.as_ptr() as *mut _,
+                image.width * 4,
+            );
+
+            if surface.is_null() {
+                sdl_panic!();
+            }
+
+            let handle = SDL_CreateColorCursor(surface, hot_x, hot_y);
+
+            SDL_DestroySurface(surface);
+
+            if handle.is_null() {
+                sdl_panic!();
+            }
+
+            Cursor(Rc::new(CursorInner { handle }))
+        }
+    }
+
+    pub fn system(cursor: SystemCursor) -> Cursor {
+        unsafe {
+            let handle = SDL_CreateSystemCursor(cursor.to_raw());
+
+            if handle.is_null() {
+                sdl_panic!();
+            }
+
+            Cursor(Rc::new(CursorInner { handle }))
+        }
+    }
+
+    pub(crate) fn raw(&self) -> *mut SDL_Cursor {
+        self.0.handle
+    }
+}
+
+impl Drop for CursorInner {
+    fn drop(&mut self) {
+        unsafe {
+            SDL_DestroyCursor(self.handle);
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use std::ffi::{CStr, CString};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+
+use sdl3_sys::dialog::*;
+use sdl3_sys::events::*;
+use sdl3_sys::messagebox::*;
+use sdl3_sys::video::*;
+
+use super::{get_err, register_event_type, Window};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MessageBoxKind {
+    Error,
+    Warning,
+    Information,
+}
+
+impl MessageBoxKind {
+    fn to_raw(self) -> SDL_MessageBoxFlags {
+        match self {
+            MessageBoxKind::Error => SDL_MESSAGEBOX_ERROR,
+            MessageBoxKind::Warning => SDL_MESSAGEBOX_WARNING,
+            MessageBoxKind::Information => SDL_MESSAGEBOX_INFORMATION,
+        }
+    }
+}
+
+/// Shows a native message box with an OK button, blocking until it is closed.
+///
+/// This can be called before any windows have been created, so it is safe to use for
+/// reporting fatal errors during startup. It can still fail (e.g. if there is no display
+/// available), so the error should be written somewhere else too, like stderr.
+pub fn show_message_box(
+    kind: MessageBoxKind,
+    title: &str,
+    message: &str,
+    window: Option<&Window>,
+) -> Result<(), String> {
+    let c_title = c_string(title)?;
+    let c_message = c_string(message)?;
+
+    unsafe {
+        if SDL_ShowSimpleMessageBox(
+            kind.to_raw(),
+            c_title.as_ptr(),
+            c_message.as_ptr(),
+            window_ptr(window),
+        ) {
+            Ok(())
+        } else {
+            Err(get_err())
+        }
+    }
+}
+
+/// Shows a native message box with custom buttons, blocking until one is picked.
+///
+/// Returns the index of the button that was picked, or `None` if the message box was
+/// closed some other way. The first button is the default for the Enter key, and the
+/// last is the default for the Escape key.
+///
+/// Like `show_message_box`, this returns an error if the message box can't be shown.
+pub fn show_message_box_with_buttons(
+    kind: MessageBoxKind,
+    title: &str,
+    message: &str,
+    buttons: &[&str],
+    window: Option<&Window>,
+) -> Result<Option<usize>, String> {
+    let c_title = c_string(title)?;
+    let c_message = c_string(message)?;
+    let c_buttons = buttons
+        .iter()
+        .map(|b| c_string(b))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let button_data: Vec<SDL_MessageBoxButtonData> = c_buttons
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let mut flags = 0;
+
+            if i == 0 {
+                flags |= SDL_MESSAGEBOX_BUTTON_RETURNKEY_DEFAULT;
+            }
+
+            if i == c_buttons.len() - 1 {
+                flags |= SDL_MESSAGEBOX_BUTTON_ESCAPEKEY_DEFAULT;
+            }
+
+            SDL_MessageBoxButtonData {
+                flags,
+                buttonID: i as i32,
+                text: text.as_ptr(),
+            }
+        })
+        .collect();
+
+    let data = SDL_MessageBoxData {
+        flags: kind.to_raw() | SDL_MESSAGEBOX_BUTTONS_LEFT_TO_RIGHT,
+        window: window_ptr(window),
+        title: c_title.as_ptr(),
+        message: c_message.as_ptr(),
+        numbuttons: button_data.len() as i32,
+        buttons: button_data.as_ptr(),
+        colorScheme: std::ptr::null(),
+    };
+
+    let mut button_id = -1;
+
+    unsafe {
+        if !SDL_ShowMessageBox(&data, &mut button_id) {
+            return Err(get_err());
+        }
+    }
+
+    if button_id >= 0 {
+        Ok(Some(button_id as usize))
+    } else {
+        Ok(None)
+    }
+}
+
+fn c_string(text: &str) -> Result<CString, String> {
+    CString::new(text).map_err(|_| format!("text contains a NUL byte: {:?}", text))
+}
+
+/// Identifies which dialog a `FileDialog` event is the result of.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DialogID(u32);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileDialogResult {
+    Selected(Vec<PathBuf>),
+    Cancelled,
+    Failed(String),
+}
+
+pub struct FileFilter<'a> {
+    /// The name shown to the user, e.g. "Images".
+    pub name: &'a str,
+
+    /// A semicolon-separated list of extensions, e.g. "png;jpg", or "*" for all files.
+    pub pattern: &'a str,
+}
+
+/// Shows a native dialog for picking files to open.
+///
+/// This does not block - the result will be delivered as an `Event::FileDialog` with the
+/// returned ID. Returns an error if any of the filters or the default location contain
+/// a NUL byte.
+pub fn show_open_file_dialog(
+    window: Option<&Window>,
+    filters: &[FileFilter<'_>],
+    default_location: Option<&str>,
+    allow_many: bool,
+) -> Result<DialogID, String> {
+    let request = DialogRequest::new(filters, default_location)?;
+    let id = request.id;
+
+    unsafe {
+        let filters = request.filters_ptr();
+        let nfilters = request.filters.len() as i32;
+        let location = request.location_ptr();
+
+        SDL_ShowOpenFileDialog(
+            Some(dialog_callback),
+            Box::into_raw(request) as *mut _,
+            window_ptr(window),
+            filters,
+            nfilters,
+            location,
+            allow_many,
+        );
+    }
+
+    Ok(id)
+}
+
+/// Shows a native dialog for picking where to save a file.
+///
+/// This does not block - the result will be delivered as an `Event::FileDialog` with the
+/// returned ID. Returns an error if any of the filters or the default location contain
+/// a NUL byte.
+pub fn show_save_file_dialog(
+    window: Option<&Window>,
+    filters: &[FileFilter<'_>],
+    default_location: Option<&str>,
+) -> Result<DialogID, String> {
+    let request = DialogRequest::new(filters, default_location)?;
+    let id = request.id;
+
+    unsafe {
+        let filters = request.filters_ptr();
+        let nfilters = request.filters.len() as i32;
+        let location = request.location_ptr();
+
+        SDL_ShowSaveFileDialog(
+            Some(dialog_callback),
+            Box::into_raw(request) as *mut _,
+            window_ptr(window),
+            filters,
+            nfilters,
+            location,
+        );
+    }
+
+    Ok(id)
+}
+
+/// Shows a native dialog for picking folders.
+///
+/// This does not block - the result will be delivered as an `Event::FileDialog` with the
+/// returned ID. Returns an error if the default location contains a NUL byte.
+pub fn show_open_folder_dialog(
+    window: Option<&Window>,
+    default_location: Option<&str>,
+    allow_many: bool,
+) -> Result<DialogID, String> {
+    let request = DialogRequest::new(&[], default_location)?;
+    let id = request.id;
+
+    unsafe {
+        let location = request.location_ptr();
+
+        SDL_ShowOpenFolderDialog(
+            Some(dialog_callback),
+            Box::into_raw(request) as *mut _,
+            window_ptr(window),
+            location,
+            allow_many,
+        );
+    }
+
+    Ok(id)
+}
+
+/// Returns the SDL event type used to deliver dialog results.
+pub(crate) fn dialog_event_type() -> u32 {
+    static EVENT_TYPE: OnceLock<u32> = OnceLock::new();
+
+    register_event_type(&EVENT_TYPE)
+}
+
+/// Takes ownership of the result attached to a dialog event.
+///
+/// # Safety
+///
+/// The event must have been pushed by `dialog_callback`, and must only be read once.
+pub(crate) unsafe fn take_dialog_result(event: &SDL_UserEvent) -> (DialogID, FileDialogResult) {
+    unsafe {
+        let result = Box::from_raw(event.data1 as *mut FileDialogResult);
+
+        (DialogID(event.code as u32), *result)
+    }
+}
+
+/// The data that needs to stay alive until SDL calls back with the dialog's result.
+struct DialogRequest {
+    id: DialogID,
+
+    _filter_strings: Vec<(CString, CString)>,
+    filters: Vec<SDL_DialogFileFilter>,
+    default_location: Option<CString>,
+}
+
+impl DialogRequest {
+    fn new(
+        filters: &[FileFilter<'_>],
+        default_location: Option<&str>,
+    ) -> Result<Box<DialogRequest>, String> {
+        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+        // The callback can run on another thread, so this needs to happen first.
+        dialog_event_type();
+
+        let filter_strings = filters
+            .iter()
+            .map(|f| Ok((c_string(f.name)?, c_string(f.pattern)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let default_location = default_location.map(c_string).transpose()?;
+
+        let raw_filters = filter_strings
+            .iter()
+            .map(|(name, pattern)| SDL_DialogFileFilter {
+                name: name.as_ptr(),
+                pattern: pattern.as_ptr(),
+            })
+            .collect();
+
+        Ok(Box::new(DialogRequest {
+            id: DialogID(NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+
+            _filter_strings: filter_strings,
+            filters: raw_filters,
+            default_location,
+        }))
+    }
+
+    fn filters_ptr(&self) -> *const SDL_DialogFileFilter {
+        if self.filters.is_empty() {
+            std::ptr::null()
+        } else {
+            self.filters.as_ptr()
+        }
+    }
+
+    fn location_ptr(&self) -> *const std::ffi::c_char {
+        self.default_location
+            .as_ref()
+            .map(|l| l.as_ptr())
+            .unwrap_or(std::ptr::null())
+    }
+}
+
+// This may be called from another thread, so the result gets sent back to the main
+// thread via the event queue.
+unsafe extern "C" fn dialog_callback(
+    userdata: *mut std::ffi::c_void,
+    filelist: *const *const std::ffi::c_char,
+    _filter: std::ffi::c_int,
+) {
+    unsafe {
+        let request = Box::from_raw(userdata as *mut DialogRequest);
+
+        let result = if filelist.is_null() {
+            FileDialogResult::Failed(get_err())
+        } else if (*filelist).is_null() {
+            FileDialogResult::Cancelled
+        } else {
+            let mut paths = Vec::new();
+            let mut current = filelist;
+
+            while !(*current).is_null() {
+                let path = CStr::from_ptr(*current).to_string_lossy().into_owned();
+                paths.push(PathBuf::from(path));
+                current = current.add(1);
+            }
+
+            FileDialogResult::Selected(paths)
+        };
+
+        let mut event: SDL_Event = std::mem::zeroed();
+
+        event.user.r#type = dialog_event_type();
+        event.user.code = request.id.0 as i32;
+        event.user.data1 = Box::into_raw(Box::new(result)) as *mut _;
+
+        if !SDL_PushEvent(&mut event) {
+            drop(Box::from_raw(event.user.data1 as *mut FileDialogResult));
+        }
+    }
+}
+
+fn window_ptr(window: Option<&Window>) -> *mut SDL_Window {
+    window.map(|w| w.window).unwrap_or(std::ptr::null_mut())
+}
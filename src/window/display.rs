@@ -0,0 +1,194 @@
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+
+use sdl3_sys::rect::*;
+use sdl3_sys::stdinc::*;
+use sdl3_sys::video::*;
+
+use crate::graphics::IRectangle;
+
+/// A handle to a connected display.
+///
+/// The ID is only valid while the display is connected - if it is disconnected and
+/// reconnected, it will get a new ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Display(SDL_DisplayID);
+
+impl Display {
+    pub fn from_raw(id: SDL_DisplayID) -> Display {
+        Display(id)
+    }
+
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns all of the currently connected displays, or an empty list if they can't
+    /// be queried.
+    pub fn all() -> Vec<Display> {
+        unsafe {
+            let mut count = 0;
+            let raw = SDL_GetDisplays(&mut count);
+
+            if raw.is_null() {
+                return Vec::new();
+            }
+
+            let displays = std::slice::from_raw_parts(raw, count as usize)
+                .iter()
+                .map(|id| Display(*id))
+                .collect();
+
+            SDL_free(raw as *mut _);
+
+            displays
+        }
+    }
+
+    /// Returns the primary display, or `None` if there isn't one (e.g. if every display
+    /// has been disconnected).
+    pub fn primary() -> Option<Display> {
+        unsafe {
+            let id = SDL_GetPrimaryDisplay();
+
+            if id == 0 {
+                None
+            } else {
+                Some(Display(id))
+            }
+        }
+    }
+
+    pub fn name(&self) -> String {
+        unsafe {
+            let raw = SDL_GetDisplayName(self.0);
+
+            if raw.is_null() {
+                return String::new();
+            }
+
+            CStr::from_ptr(raw).to_string_lossy().into_owned()
+        }
+    }
+
+    /// Returns the area of the desktop that this display covers.
+    pub fn bounds(&self) -> IRectangle {
+        unsafe {
+            let mut rect = SDL_Rect {
+                x: 0,
+                y: 0,
+                w: 0,
+                h: 0,
+            };
+
+            SDL_GetDisplayBounds(self.0, &mut rect);
+
+            IRectangle::new(rect.x, rect.y, rect.w, rect.h)
+        }
+    }
+
+    /// Returns the area of the display that is not covered by things like task bars
+    /// and menu bars.
+    pub fn usable_bounds(&self) -> IRectangle {
+        unsafe {
+            let mut rect = SDL_Rect {
+                x: 0,
+                y: 0,
+                w: 0,
+                h: 0,
+            };
+
+            SDL_GetDisplayUsableBounds(self.0, &mut rect);
+
+            IRectangle::new(rect.x, rect.y, rect.w, rect.h)
+        }
+    }
+
+    /// Returns the scale that the user has requested UI be displayed at.
+    pub fn content_scale(&self) -> f32 {
+        unsafe { SDL_GetDisplayContentScale(self.0) }
+    }
+
+    /// Returns the refresh rate of the display's current mode, or 0.0 if unknown.
+    pub fn refresh_rate(&self) -> f32 {
+        self.current_mode().map(|m| m.refresh_rate).unwrap_or(0.0)
+    }
+
+    /// Returns the mode that the desktop is running at, ignoring any exclusive
+    /// fullscreen mode changes.
+    pub fn desktop_mode(&self) -> Option<DisplayMode> {
+        unsafe { DisplayMode::from_ptr(SDL_GetDesktopDisplayMode(self.0)) }
+    }
+
+    pub fn current_mode(&self) -> Option<DisplayMode> {
+        unsafe { DisplayMode::from_ptr(SDL_GetCurrentDisplayMode(self.0)) }
+    }
+
+    /// Returns the modes that can be used for exclusive fullscreen on this display,
+    /// ordered from largest to smallest. This will be empty if the display has been
+    /// disconnected.
+    pub fn fullscreen_modes(&self) -> Vec<DisplayMode> {
+        unsafe {
+            let mut count = 0;
+            let raw = SDL_GetFullscreenDisplayModes(self.0, &mut count);
+
+            if raw.is_null() {
+                return Vec::new();
+            }
+
+            let modes = std::slice::from_raw_parts(raw, count as usize)
+                .iter()
+                .filter_map(|mode| DisplayMode::from_ptr(*mode))
+                .collect();
+
+            SDL_free(raw as *mut _);
+
+            modes
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayMode {
+    pub display: Display,
+    pub width: i32,
+    pub height: i32,
+    pub pixel_density: f32,
+    pub refresh_rate: f32,
+}
+
+impl DisplayMode {
+    unsafe fn from_ptr(raw: *const SDL_DisplayMode) -> Option<DisplayMode> {
+        unsafe {
+            raw.as_ref().map(|mode| DisplayMode {
+                display: Display(mode.displayID),
+                width: mode.w,
+                height: mode.h,
+                pixel_density: mode.pixel_density,
+                refresh_rate: mode.refresh_rate,
+            })
+        }
+    }
+
+    /// Returns the closest available fullscreen mode, or `None` if there isn't one (e.g.
+    /// if the display has been disconnected, or the mode is larger than any available).
+    pub(crate) fn to_raw(self) -> Option<SDL_DisplayMode> {
+        unsafe {
+            let mut raw = MaybeUninit::uninit();
+
+            if SDL_GetClosestFullscreenDisplayMode(
+                self.display.0,
+                self.width,
+                self.height,
+                self.refresh_rate,
+                self.pixel_density > 1.0,
+                raw.as_mut_ptr(),
+            ) {
+                Some(raw.assume_init())
+            } else {
+                None
+            }
+        }
+    }
+}